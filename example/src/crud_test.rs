@@ -9,6 +9,8 @@ mod test {
     use rbatis::plugin::logic_delete::RbatisLogicDeletePlugin;
     use rbatis::plugin::page::{Page, PageRequest};
     use rbatis::rbatis::Rbatis;
+    use rbatis::wrapper::Wrapper;
+    use rbatis_core::db::DriverType;
     use rbatis_core::Error;
     use rbatis_core::types::BigDecimal;
     use rbatis_core::types::chrono::NaiveDateTime;
@@ -289,4 +291,34 @@ mod test {
         let results = join_select(&RB, "test").await.unwrap();
         println!("data: {:?}", results);
     }
+
+    #[derive(CRUDEnable, Serialize, Deserialize, Clone, Debug)]
+    #[rbatis(table = "t_user")]
+    pub struct User {
+        #[rbatis(id, column = "user_id")]
+        pub id: Option<i64>,
+        #[rbatis(column = "user_name")]
+        pub name: Option<String>,
+        pub table_name: Option<String>,
+    }
+
+    //the getter not clash with the column consts(UserColumns::name)
+    impl User {
+        pub fn name(&self) -> &str {
+            self.name.as_deref().unwrap_or_default()
+        }
+    }
+
+    #[test]
+    pub fn test_columns() {
+        let w = Wrapper::new(&DriverType::Mysql)
+            .eq(UserColumns::name, "zhangsan")
+            .eq(UserColumns::table_name, "t")
+            .order_by(false, &[UserColumns::id])
+            .check().unwrap();
+        assert_eq!(w.sql, "user_name = ? AND table_name = ? ORDER BY user_id DESC");
+        assert_eq!(User::table_name(), "t_user");
+        let user = User { id: Some(1), name: Some("zhangsan".to_string()), table_name: None };
+        assert_eq!(user.name(), "zhangsan");
+    }
 }
\ No newline at end of file
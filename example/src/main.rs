@@ -215,6 +215,26 @@ pub async fn test_tx() {
     rb.commit(tx_id).await.unwrap();
 }
 
+//示例-Rbatis使用savepoint嵌套事务
+#[async_std::test]
+pub async fn test_tx_savepoint() {
+    fast_log::log::init_log("requests.log", &RuntimeType::Std).unwrap();
+    let rb = Rbatis::new();
+    rb.link(MYSQL_URL).await.unwrap();
+    let tx_id = "1";
+    rb.begin(tx_id).await.unwrap();
+    //begin again is error
+    assert!(rb.begin(tx_id).await.is_err());
+    rb.exec(tx_id, "UPDATE biz_activity SET remark = 'outer' WHERE id = '1';").await.unwrap();
+    assert_eq!(rb.begin_savepoint(tx_id).await.unwrap(), 1);
+    rb.exec(tx_id, "UPDATE biz_activity SET remark = 'nested' WHERE id = '1';").await.unwrap();
+    assert_eq!(rb.rollback_to_savepoint(tx_id).await.unwrap(), 0);
+    let v: serde_json::Value = rb.fetch(tx_id, "SELECT remark FROM biz_activity WHERE id = '1';").await.unwrap();
+    println!("{}", v.clone());
+    assert!(rb.release_savepoint(tx_id).await.is_err());
+    rb.rollback(tx_id).await.unwrap();
+}
+
 /// 示例-Rbatis使用web框架Tide、async_std
 #[async_std::test]
 pub async fn test_tide() {
@@ -75,6 +75,17 @@ where
 
     /// fetch into an json array
     fn fetch_json(&mut self) -> BoxFuture<Result<Vec<serde_json::Value>, crate::Error>>;
+
+    /// fetch the next row into an json object,return None if no more row
+    fn fetch_json_next(&mut self) -> BoxFuture<Result<Option<serde_json::Value>, crate::Error>>;
+
+    /// fetch all rows and decode every row into T directly(without the json object)
+    fn fetch_decode<T>(&mut self) -> BoxFuture<Result<Vec<T>, crate::Error>>
+        where T: DeserializeOwned + Send;
+
+    /// fetch the next row and decode into T directly,return None if no more row
+    fn fetch_decode_next<T>(&mut self) -> BoxFuture<Result<Option<T>, crate::Error>>
+        where T: DeserializeOwned + Send;
 }
 
 // Prevent users from implementing the `Row` trait.
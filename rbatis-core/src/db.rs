@@ -1,11 +1,19 @@
+use std::borrow::Cow;
+use std::ops::DerefMut;
+use std::str::FromStr;
 use std::time::Duration;
 
+use async_stream::try_stream;
+use futures_core::future::BoxFuture;
+use futures_core::stream::BoxStream;
+use futures_util::StreamExt;
 use serde::{Deserialize, Serialize};
 use serde::de::DeserializeOwned;
 
 use crate::connection::Connection;
 use crate::cursor::Cursor;
 use crate::database::Database;
+use crate::decode::json_decode;
 use crate::encode::Encode;
 use crate::Error;
 use crate::executor::Executor;
@@ -14,10 +22,11 @@ use crate::pool::Builder;
 use crate::pool::PoolConnection;
 use crate::postgres::{PgConnection, PgCursor, PgPool, Postgres};
 use crate::query::{Query, query};
-use crate::runtime::Mutex;
+use crate::runtime::{Mutex, spawn};
 use crate::sqlite::{Sqlite, SqliteConnection, SqliteCursor, SqlitePool};
 use crate::transaction::Transaction;
-use crate::types::Type;
+use crate::types::{BigDecimal, Json, Type};
+use crate::types::chrono::NaiveDateTime;
 
 #[derive(Debug, Clone, Copy)]
 pub struct PoolOptions {
@@ -143,38 +152,7 @@ impl DBPool {
     }
 
     pub fn make_query<'f, 's>(&'f self, sql: &'s str) -> crate::Result<DBQuery<'s>> {
-        match &self.driver_type {
-            &DriverType::None => {
-                return Err(Error::from("un init DBPool!"));
-            }
-            &DriverType::Mysql => {
-                let q: Query<MySql> = query(sql);
-                return Ok(DBQuery {
-                    driver_type: DriverType::Mysql,
-                    mysql: Some(q),
-                    postgres: None,
-                    sqlite: None,
-                });
-            }
-            &DriverType::Postgres => {
-                let q: Query<Postgres> = query(sql);
-                return Ok(DBQuery {
-                    driver_type: DriverType::Postgres,
-                    mysql: None,
-                    postgres: Some(q),
-                    sqlite: None,
-                });
-            }
-            &DriverType::Sqlite => {
-                let q: Query<Sqlite> = query(sql);
-                return Ok(DBQuery {
-                    driver_type: DriverType::Sqlite,
-                    mysql: None,
-                    postgres: None,
-                    sqlite: Some(q),
-                });
-            }
-        }
+        DBQuery::new(&self.driver_type, sql)
     }
     /// Retrieves a connection from the pool.
     ///
@@ -225,6 +203,7 @@ impl DBPool {
                     mysql: Some(self.mysql.as_ref().unwrap().begin().await?),
                     postgres: None,
                     sqlite: None,
+                    savepoint_depth: 0,
                 })
             }
             &DriverType::Postgres => {
@@ -233,6 +212,7 @@ impl DBPool {
                     mysql: None,
                     postgres: Some(self.postgres.as_ref().unwrap().begin().await?),
                     sqlite: None,
+                    savepoint_depth: 0,
                 })
             }
             &DriverType::Sqlite => {
@@ -241,6 +221,7 @@ impl DBPool {
                     mysql: None,
                     postgres: None,
                     sqlite: Some(Mutex::new(self.sqlite.as_ref().unwrap().begin().await?)),
+                    savepoint_depth: 0,
                 })
             }
         }
@@ -290,90 +271,109 @@ pub struct DBQuery<'q> {
 }
 
 impl<'q> DBQuery<'q> {
+    pub fn new(driver_type: &DriverType, sql: &'q str) -> crate::Result<DBQuery<'q>> {
+        match driver_type {
+            &DriverType::None => {
+                return Err(Error::from("un init DBPool!"));
+            }
+            &DriverType::Mysql => {
+                let q: Query<MySql> = query(sql);
+                return Ok(DBQuery {
+                    driver_type: DriverType::Mysql,
+                    mysql: Some(q),
+                    postgres: None,
+                    sqlite: None,
+                });
+            }
+            &DriverType::Postgres => {
+                let q: Query<Postgres> = query(sql);
+                return Ok(DBQuery {
+                    driver_type: DriverType::Postgres,
+                    mysql: None,
+                    postgres: Some(q),
+                    sqlite: None,
+                });
+            }
+            &DriverType::Sqlite => {
+                let q: Query<Sqlite> = query(sql);
+                return Ok(DBQuery {
+                    driver_type: DriverType::Sqlite,
+                    mysql: None,
+                    postgres: None,
+                    sqlite: Some(q),
+                });
+            }
+        }
+    }
+
+    /// bind an json value as sql arg,the json value will convert into BindArg by the driver type first,
+    /// see BindArg::from_json()
     pub fn bind_value(&mut self, t: &serde_json::Value) -> crate::Result<()> {
+        let arg = BindArg::from_json(&self.driver_type, t)?;
+        self.bind_arg(arg)
+    }
+
+    /// bind an BindArg as sql arg,sqlite bind the decimal and timestamp as TEXT
+    pub fn bind_arg(&mut self, arg: BindArg) -> crate::Result<()> {
         match &self.driver_type {
             &DriverType::None => {
                 return Err(Error::from("un init DBPool!"));
             }
             &DriverType::Mysql => {
                 let mut q = self.mysql.take().unwrap();
-                match t {
-                    serde_json::Value::String(s) => {
-                        q = q.bind(Some(s));
-                    }
-                    serde_json::Value::Null => {
-                        q = q.bind(Option::<String>::None);
-                    }
-                    serde_json::Value::Number(n) => {
-                        if n.is_f64() {
-                            q = q.bind(n.as_f64().unwrap());
-                        } else if n.is_u64() {
-                            q = q.bind(n.as_f64().unwrap());
-                        } else if n.is_i64() {
-                            q = q.bind(n.as_i64().unwrap());
-                        }
-                    }
-                    serde_json::Value::Bool(b) => {
-                        q = q.bind(Option::Some(b));
-                    }
+                q = match arg {
+                    BindArg::Null => q.bind(Option::<String>::None),
+                    BindArg::Bool(b) => q.bind(b),
+                    BindArg::I64(n) => q.bind(n),
+                    BindArg::U64(n) => q.bind(n),
+                    BindArg::F64(n) => q.bind(n),
+                    BindArg::Decimal(n) => q.bind(n),
+                    BindArg::Text(s) => q.bind(s.as_ref()),
+                    BindArg::Json(v) => q.bind(v.to_string()),
+                    BindArg::DateTime(t) => q.bind(t),
                     _ => {
-                        q = q.bind(Some(t.to_string().as_str()));
+                        return Err(Error::from(format!("[rbatis] mysql not support bind arg: {:?}", arg)));
                     }
-                }
+                };
                 self.mysql = Some(q);
             }
             &DriverType::Postgres => {
                 let mut q = self.postgres.take().unwrap();
-                match t {
-                    serde_json::Value::String(s) => {
-                        q = q.bind(Some(s));
-                    }
-                    serde_json::Value::Null => {
-                        q = q.bind(Option::<String>::None);
-                    }
-                    serde_json::Value::Number(n) => {
-                        if n.is_f64() {
-                            q = q.bind(n.as_f64().unwrap());
-                        } else if n.is_u64() {
-                            q = q.bind(n.as_f64().unwrap());
-                        } else if n.is_i64() {
-                            q = q.bind(n.as_i64().unwrap());
-                        }
-                    }
-                    serde_json::Value::Bool(b) => {
-                        q = q.bind(Option::Some(b));
-                    }
+                q = match arg {
+                    BindArg::Null => q.bind(Option::<String>::None),
+                    BindArg::Bool(b) => q.bind(b),
+                    BindArg::I64(n) => q.bind(n),
+                    BindArg::F64(n) => q.bind(n),
+                    BindArg::Decimal(n) => q.bind(n),
+                    BindArg::Text(s) => q.bind(s.as_ref()),
+                    BindArg::Json(v) => q.bind(Json(v)),
+                    BindArg::DateTime(t) => q.bind(t),
+                    BindArg::BoolArray(arr) => q.bind(arr),
+                    BindArg::I64Array(arr) => q.bind(arr),
+                    BindArg::F64Array(arr) => q.bind(arr),
+                    BindArg::DecimalArray(arr) => q.bind(arr),
+                    BindArg::TextArray(arr) => q.bind(arr),
                     _ => {
-                        q = q.bind(Some(t.to_string().as_str()));
+                        return Err(Error::from(format!("[rbatis] postgres not support bind arg: {:?}", arg)));
                     }
-                }
+                };
                 self.postgres = Some(q);
             }
             &DriverType::Sqlite => {
                 let mut q = self.sqlite.take().unwrap();
-                match t {
-                    serde_json::Value::String(s) => {
-                        q = q.bind(Some(s));
-                    }
-                    serde_json::Value::Null => {
-                        q = q.bind(Option::<String>::None);
-                    }
-                    serde_json::Value::Number(n) => {
-                        if n.is_f64() {
-                            q = q.bind(n.as_f64().unwrap());
-                        } else if n.is_u64() {
-                            q = q.bind(n.as_f64().unwrap());
-                        } else if n.is_i64() {
-                            q = q.bind(n.as_i64().unwrap());
-                        }
-                    }
-                    serde_json::Value::Bool(b) => {
-                        q = q.bind(Option::Some(b));
-                    }
+                q = match arg {
+                    BindArg::Null => q.bind(Option::<String>::None),
+                    BindArg::Bool(b) => q.bind(b),
+                    BindArg::I64(n) => q.bind(n),
+                    BindArg::F64(n) => q.bind(n),
+                    BindArg::Decimal(n) => q.bind(n.to_string()),
+                    BindArg::Text(s) => q.bind(s.as_ref()),
+                    BindArg::Json(v) => q.bind(v.to_string()),
+                    BindArg::DateTime(t) => q.bind(t.format(TIMESTAMP_FORMAT).to_string()),
                     _ => {
-                        q = q.bind(Some(t.to_string().as_str()));
+                        return Err(Error::from(format!("[rbatis] sqlite not support bind arg: {:?}", arg)));
                     }
-                }
+                };
                 self.sqlite = Some(q);
             }
         }
@@ -381,6 +381,154 @@ impl<'q> DBQuery<'q> {
     }
 }
 
+/// the json object key which mark an array bind as postgres native array,see BindArg::array()
+pub const ARRAY_ARG: &str = "$rbatis_array";
+/// the json object key which mark an string bind as timestamp(postgres TIMESTAMP,mysql DATETIME),see BindArg::timestamp()
+pub const TIMESTAMP_ARG: &str = "$rbatis_timestamp";
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.f";
+
+/// the sql arg which convert from json value,the number will keep it's width/precision
+#[derive(Debug, Clone, PartialEq)]
+pub enum BindArg<'a> {
+    Null,
+    Bool(bool),
+    I64(i64),
+    U64(u64),
+    F64(f64),
+    Decimal(BigDecimal),
+    Text(Cow<'a, str>),
+    Json(&'a serde_json::Value),
+    BoolArray(Vec<bool>),
+    I64Array(Vec<i64>),
+    F64Array(Vec<f64>),
+    DecimalArray(Vec<BigDecimal>),
+    TextArray(Vec<String>),
+    DateTime(NaiveDateTime),
+}
+
+impl<'a> BindArg<'a> {
+    /// convert json value into the arg which the driver support
+    ///
+    /// mysql: u64 bind as BIGINT UNSIGNED,array/object bind as json string
+    /// postgres: u64(> i64::MAX) bind as NUMERIC,array/object bind as JSONB(use BindArg::array() for pg array)
+    /// BindArg::timestamp() bind as TIMESTAMP(postgres)/DATETIME(mysql)/TEXT(sqlite)
+    /// sqlite: u64(> i64::MAX) and decimal bind as TEXT,array/object bind as json string
+    pub fn from_json(driver_type: &DriverType, v: &'a serde_json::Value) -> crate::Result<BindArg<'a>> {
+        let arg = match v {
+            serde_json::Value::Null => BindArg::Null,
+            serde_json::Value::Bool(b) => BindArg::Bool(*b),
+            serde_json::Value::String(s) => BindArg::Text(Cow::Borrowed(s.as_str())),
+            serde_json::Value::Number(n) => Self::from_number(n)?,
+            serde_json::Value::Array(_) => BindArg::Json(v),
+            serde_json::Value::Object(m) => Self::from_typed(driver_type, m)?.unwrap_or(BindArg::Json(v)),
+        };
+        match (driver_type, arg) {
+            (&DriverType::None, _) => Err(Error::from("un init DBPool!")),
+            (&DriverType::Postgres, BindArg::U64(n)) => Ok(BindArg::Decimal(BigDecimal::from(n))),
+            (&DriverType::Sqlite, BindArg::U64(n)) => Ok(BindArg::Text(Cow::Owned(n.to_string()))),
+            (_, arg) => Ok(arg),
+        }
+    }
+
+    /// mark the array bind as postgres native array(other driver still bind as json),for Example:
+    /// w.push_sql("id = ANY(").push_stmt_arg(BindArg::array(&[1, 2])).push_sql(")");
+    pub fn array<T>(arr: &[T]) -> serde_json::Value where T: Serialize {
+        let mut m = serde_json::Map::new();
+        m.insert(ARRAY_ARG.to_string(), serde_json::json!(arr));
+        serde_json::Value::Object(m)
+    }
+
+    /// mark the time bind as timestamp(sqlite still bind as TEXT),for Example:
+    /// w.eq("create_time", BindArg::timestamp(&Local::now().naive_local()));
+    pub fn timestamp(t: &NaiveDateTime) -> serde_json::Value {
+        let mut m = serde_json::Map::new();
+        m.insert(TIMESTAMP_ARG.to_string(), serde_json::Value::String(t.format(TIMESTAMP_FORMAT).to_string()));
+        serde_json::Value::Object(m)
+    }
+
+    /// the arg which marked by BindArg::array(),BindArg::timestamp(). return None if not
+    fn from_typed(driver_type: &DriverType, m: &'a serde_json::Map<String, serde_json::Value>) -> crate::Result<Option<BindArg<'a>>> {
+        if m.len() != 1 {
+            return Ok(None);
+        }
+        match (m.get(ARRAY_ARG), m.get(TIMESTAMP_ARG)) {
+            (Some(serde_json::Value::Array(arr)), _) => {
+                match driver_type {
+                    &DriverType::Postgres => {
+                        Ok(Some(Self::from_array(arr)?.ok_or_else(|| Error::from(format!("[rbatis] pg array items must be all bool/number/string: {}", m[ARRAY_ARG])))?))
+                    }
+                    _ => Ok(Some(BindArg::Json(&m[ARRAY_ARG]))),
+                }
+            }
+            (_, Some(serde_json::Value::String(s))) => {
+                match driver_type {
+                    &DriverType::Sqlite => Ok(Some(BindArg::Text(Cow::Borrowed(s.as_str())))),
+                    _ => match NaiveDateTime::parse_from_str(s, TIMESTAMP_FORMAT) {
+                        Ok(t) => Ok(Some(BindArg::DateTime(t))),
+                        Err(e) => Err(Error::from(format!("[rbatis] bind timestamp: {} fail: {}", s, e)))
+                    }
+                }
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn from_number(n: &serde_json::Number) -> crate::Result<BindArg<'a>> {
+        if let Some(n) = n.as_i64() {
+            return Ok(BindArg::I64(n));
+        }
+        if let Some(n) = n.as_u64() {
+            return Ok(BindArg::U64(n));
+        }
+        let s = n.to_string();
+        match n.as_f64() {
+            //f64 is lossless only if it print same as the json number(arbitrary_precision number may not)
+            Some(f) if serde_json::Number::from_f64(f).map(|f| f.to_string()) == Some(s.clone()) => Ok(BindArg::F64(f)),
+            _ => match BigDecimal::from_str(&s) {
+                Ok(d) => Ok(BindArg::Decimal(d)),
+                Err(e) => Err(Error::from(format!("[rbatis] bind number: {} fail: {}", s, e)))
+            }
+        }
+    }
+
+    /// pg array,the items must be all bool/number/string. return None if not
+    fn from_array(arr: &'a Vec<serde_json::Value>) -> crate::Result<Option<BindArg<'a>>> {
+        let mut items = vec![];
+        for x in arr {
+            match x {
+                serde_json::Value::Bool(b) => items.push(BindArg::Bool(*b)),
+                serde_json::Value::String(s) => items.push(BindArg::Text(Cow::Borrowed(s.as_str()))),
+                serde_json::Value::Number(n) => items.push(Self::from_number(n)?),
+                _ => return Ok(None),
+            }
+        }
+        if items.iter().all(|x| match x { BindArg::Text(_) => true, _ => false }) {
+            //empty array bind as TEXT[]
+            return Ok(Some(BindArg::TextArray(items.into_iter().map(|x| match x { BindArg::Text(s) => s.into_owned(), _ => String::new() }).collect())));
+        }
+        if items.iter().all(|x| match x { BindArg::Bool(_) => true, _ => false }) {
+            return Ok(Some(BindArg::BoolArray(items.into_iter().map(|x| x == BindArg::Bool(true)).collect())));
+        }
+        if items.iter().all(|x| match x { BindArg::I64(_) => true, _ => false }) {
+            return Ok(Some(BindArg::I64Array(items.into_iter().map(|x| match x { BindArg::I64(n) => n, _ => 0 }).collect())));
+        }
+        if items.iter().all(|x| match x { BindArg::I64(_) | BindArg::F64(_) => true, _ => false }) {
+            return Ok(Some(BindArg::F64Array(items.into_iter().map(|x| match x { BindArg::I64(n) => n as f64, BindArg::F64(n) => n, _ => 0.0 }).collect())));
+        }
+        let mut decimals = vec![];
+        for x in items {
+            match x {
+                BindArg::I64(n) => decimals.push(BigDecimal::from(n)),
+                BindArg::U64(n) => decimals.push(BigDecimal::from(n)),
+                BindArg::F64(n) => decimals.push(BigDecimal::from_str(&n.to_string()).map_err(|e| Error::from(e.to_string()))?),
+                BindArg::Decimal(n) => decimals.push(n),
+                _ => return Ok(None),
+            }
+        }
+        Ok(Some(BindArg::DecimalArray(decimals)))
+    }
+}
+
 
 pub struct DBPoolConn {
     pub driver_type: DriverType,
@@ -501,6 +649,46 @@ impl DBPoolConn {
         }
     }
 
+    /// the last insert id of this connection
+    /// mysql: the last_insert_id of the OK packet(first id of the multiple-row insert)
+    /// sqlite: last_insert_rowid()
+    /// postgres: not support,please use 'RETURNING' sql
+    pub fn last_insert_id(&mut self) -> crate::Result<i64> {
+        match &self.driver_type {
+            &DriverType::None => {
+                return Err(Error::from("un init DBPool!"));
+            }
+            &DriverType::Mysql => {
+                let data = self.mysql.as_ref().unwrap().last_insert_id();
+                return Ok(data as i64);
+            }
+            &DriverType::Postgres => {
+                return Err(Error::from("[rbatis] postgres not support last_insert_id,please use 'RETURNING' sql!"));
+            }
+            &DriverType::Sqlite => {
+                let data = self.sqlite.as_mut().unwrap().last_insert_rowid();
+                return Ok(data);
+            }
+        }
+    }
+
+    /// fetch the rows as stream(prepare sql),every row will decode into T one by one.
+    /// the conn,sql and args will move into the stream
+    pub fn fetch_stream<'a, T>(mut self, sql: String, args: Vec<serde_json::Value>) -> BoxStream<'a, crate::Result<T>>
+        where T: DeserializeOwned + Send + 'a {
+        let stream: BoxStream<'a, crate::Result<serde_json::Value>> = Box::pin(try_stream! {
+            let mut q = DBQuery::new(&self.driver_type, sql.as_str())?;
+            for x in &args {
+                q.bind_value(x)?;
+            }
+            let mut cursor = self.fetch_parperd(q)?;
+            while let Some(row) = cursor.fetch_json_next().await? {
+                yield row;
+            }
+        });
+        Box::pin(stream.map(|row| json_decode::<T>(vec![row?])))
+    }
+
     pub async fn begin(self) -> crate::Result<DBTx> {
         match &self.driver_type {
             &DriverType::None => {
@@ -513,6 +701,7 @@ impl DBPoolConn {
                     mysql: Some(data),
                     postgres: None,
                     sqlite: None,
+                    savepoint_depth: 0,
                 });
             }
             &DriverType::Postgres => {
@@ -522,6 +711,7 @@ impl DBPoolConn {
                     mysql: None,
                     postgres: Some(data),
                     sqlite: None,
+                    savepoint_depth: 0,
                 });
             }
             &DriverType::Sqlite => {
@@ -531,6 +721,7 @@ impl DBPoolConn {
                     mysql: None,
                     postgres: None,
                     sqlite: Some(Mutex::new(data)),
+                    savepoint_depth: 0,
                 });
             }
         }
@@ -568,6 +759,24 @@ impl<'c, 'q> DBCursor<'c, 'q> {
         }
     }
 
+    /// fetch the next row json,return None if no more row
+    pub async fn fetch_json_next(&mut self) -> Result<Option<serde_json::Value>, crate::Error> {
+        match &self.driver_type {
+            &DriverType::None => {
+                return Err(Error::from("un init DBPool!"));
+            }
+            &DriverType::Mysql => {
+                return self.mysql.as_mut().unwrap().fetch_json_next().await;
+            }
+            &DriverType::Postgres => {
+                return self.postgres.as_mut().unwrap().fetch_json_next().await;
+            }
+            &DriverType::Sqlite => {
+                return self.sqlite.as_mut().unwrap().fetch_json_next().await;
+            }
+        }
+    }
+
     /// only fetch json
     pub async fn fetch_json(&mut self) -> Result<Vec<serde_json::Value>, crate::Error> {
         match &self.driver_type {
@@ -588,17 +797,86 @@ impl<'c, 'q> DBCursor<'c, 'q> {
             }
         }
     }
+
+    /// fetch all rows and decode every row into T directly(without the json object)
+    pub async fn fetch_decode<T>(&mut self) -> Result<Vec<T>, crate::Error>
+        where T: DeserializeOwned + Send {
+        match &self.driver_type {
+            &DriverType::None => {
+                return Err(Error::from("un init DBPool!"));
+            }
+            &DriverType::Mysql => {
+                return self.mysql.as_mut().unwrap().fetch_decode().await;
+            }
+            &DriverType::Postgres => {
+                return self.postgres.as_mut().unwrap().fetch_decode().await;
+            }
+            &DriverType::Sqlite => {
+                return self.sqlite.as_mut().unwrap().fetch_decode().await;
+            }
+        }
+    }
 }
 
 
+/// for example: SAVEPOINT _rbatis_savepoint_1
+fn savepoint_sql(stmt: &str, depth: u32) -> String {
+    format!("{} _rbatis_savepoint_{}", stmt, depth)
+}
+
 pub struct DBTx {
     pub driver_type: DriverType,
     pub mysql: Option<Transaction<PoolConnection<MySqlConnection>>>,
     pub postgres: Option<Transaction<PoolConnection<PgConnection>>>,
     pub sqlite: Option<Mutex<Transaction<PoolConnection<SqliteConnection>>>>,
+    /// the count of savepoints(nested tx) not released
+    pub savepoint_depth: u32,
 }
 
 impl DBTx {
+    /// rollback in an spawned task(for example,drop without await),the conn return to the pool after rollback
+    /// return the future of the spawned task,await it to wait the rollback done(drop it will not cancel the task)
+    pub fn spawn_rollback(mut self) -> BoxFuture<'static, ()> {
+        let handle = spawn(async move {
+            if let Err(e) = self.rollback().await {
+                log::error!("[rbatis] rollback fail:{}", e);
+            }
+        });
+        Box::pin(async move {
+            //tokio JoinHandle output is Result
+            let _ = handle.await;
+        })
+    }
+
+    /// create an savepoint(nested tx) in this tx,return the savepoint depth(start with 1)
+    pub async fn savepoint(&mut self) -> crate::Result<u32> {
+        let depth = self.savepoint_depth + 1;
+        self.execute(&savepoint_sql("SAVEPOINT", depth)).await?;
+        self.savepoint_depth = depth;
+        Ok(depth)
+    }
+
+    /// release(commit) the last savepoint,return the savepoint depth after release
+    pub async fn release_savepoint(&mut self) -> crate::Result<u32> {
+        if self.savepoint_depth == 0 {
+            return Err(Error::from("[rbatis] no savepoint to release!"));
+        }
+        self.execute(&savepoint_sql("RELEASE SAVEPOINT", self.savepoint_depth)).await?;
+        self.savepoint_depth -= 1;
+        Ok(self.savepoint_depth)
+    }
+
+    /// rollback to the last savepoint and release it,return the savepoint depth after rollback
+    pub async fn rollback_to_savepoint(&mut self) -> crate::Result<u32> {
+        if self.savepoint_depth == 0 {
+            return Err(Error::from("[rbatis] no savepoint to rollback!"));
+        }
+        self.execute(&savepoint_sql("ROLLBACK TO SAVEPOINT", self.savepoint_depth)).await?;
+        self.execute(&savepoint_sql("RELEASE SAVEPOINT", self.savepoint_depth)).await?;
+        self.savepoint_depth -= 1;
+        Ok(self.savepoint_depth)
+    }
+
     pub async fn commit(&mut self) -> crate::Result<DBPoolConn> {
         match &self.driver_type {
             &DriverType::None => {
@@ -783,4 +1061,149 @@ impl DBTx {
             }
         }
     }
-}
\ No newline at end of file
+
+    /// the last insert id of this transaction's connection
+    /// mysql: the last_insert_id of the OK packet(first id of the multiple-row insert)
+    /// sqlite: last_insert_rowid()
+    /// postgres: not support,please use 'RETURNING' sql
+    pub fn last_insert_id(&mut self) -> crate::Result<i64> {
+        match &self.driver_type {
+            &DriverType::None => {
+                return Err(Error::from("un init DBPool!"));
+            }
+            &DriverType::Mysql => {
+                let data = self.mysql.as_ref().unwrap().last_insert_id();
+                return Ok(data as i64);
+            }
+            &DriverType::Postgres => {
+                return Err(Error::from("[rbatis] postgres not support last_insert_id,please use 'RETURNING' sql!"));
+            }
+            &DriverType::Sqlite => {
+                let data = self.sqlite.as_mut().unwrap().get_mut().last_insert_rowid();
+                return Ok(data);
+            }
+        }
+    }
+
+    /// fetch the rows as stream(prepare sql),every row will decode into T one by one.
+    /// tx can be &mut DBTx or an guard of DBTx(for example the dashmap RefMut)
+    pub fn fetch_stream<'a, T, D>(mut tx: D, sql: String, args: Vec<serde_json::Value>) -> BoxStream<'a, crate::Result<T>>
+        where T: DeserializeOwned + Send + 'a, D: DerefMut<Target=DBTx> + Send + 'a {
+        let stream: BoxStream<'a, crate::Result<serde_json::Value>> = Box::pin(try_stream! {
+            let mut q = DBQuery::new(&tx.driver_type, sql.as_str())?;
+            for x in &args {
+                q.bind_value(x)?;
+            }
+            let mut cursor = tx.fetch_parperd(q)?;
+            while let Some(row) = cursor.fetch_json_next().await? {
+                yield row;
+            }
+        });
+        Box::pin(stream.map(|row| json_decode::<T>(vec![row?])))
+    }
+}
+#[cfg(test)]
+mod test {
+    use std::borrow::Cow;
+    use std::str::FromStr;
+
+    use crate::db::{BindArg, DBPool, DBQuery, DriverType, TIMESTAMP_ARG};
+    use crate::types::BigDecimal;
+    use crate::types::chrono::NaiveDateTime;
+
+    #[test]
+    fn test_bind_arg_mysql() {
+        let t = DriverType::Mysql;
+        let big = serde_json::json!(u64::MAX);
+        assert_eq!(BindArg::from_json(&t, &big).unwrap(), BindArg::U64(u64::MAX));
+        let n = serde_json::json!(-1);
+        assert_eq!(BindArg::from_json(&t, &n).unwrap(), BindArg::I64(-1));
+        let n = serde_json::json!(1);
+        assert_eq!(BindArg::from_json(&t, &n).unwrap(), BindArg::I64(1));
+        let f = serde_json::json!(1.5);
+        assert_eq!(BindArg::from_json(&t, &f).unwrap(), BindArg::F64(1.5));
+        let arr = serde_json::json!([1, 2]);
+        assert_eq!(BindArg::from_json(&t, &arr).unwrap(), BindArg::Json(&arr));
+        let obj = serde_json::json!({"a": 1});
+        assert_eq!(BindArg::from_json(&t, &obj).unwrap(), BindArg::Json(&obj));
+    }
+
+    #[test]
+    fn test_bind_arg_postgres() {
+        let t = DriverType::Postgres;
+        let big = serde_json::json!(u64::MAX);
+        assert_eq!(BindArg::from_json(&t, &big).unwrap(), BindArg::Decimal(BigDecimal::from_str("18446744073709551615").unwrap()));
+        let n = serde_json::json!(i64::MAX);
+        assert_eq!(BindArg::from_json(&t, &n).unwrap(), BindArg::I64(i64::MAX));
+        //json/jsonb column
+        let arr = serde_json::json!([1, 2]);
+        assert_eq!(BindArg::from_json(&t, &arr).unwrap(), BindArg::Json(&arr));
+        let arr = BindArg::array(&[1, 2]);
+        assert_eq!(BindArg::from_json(&t, &arr).unwrap(), BindArg::I64Array(vec![1, 2]));
+        let arr = BindArg::array(&[1.0, 2.5]);
+        assert_eq!(BindArg::from_json(&t, &arr).unwrap(), BindArg::F64Array(vec![1.0, 2.5]));
+        let arr = BindArg::array(&[1, u64::MAX]);
+        assert_eq!(BindArg::from_json(&t, &arr).unwrap(), BindArg::DecimalArray(vec![BigDecimal::from(1), BigDecimal::from(u64::MAX)]));
+        let arr = BindArg::array(&["a", "b"]);
+        assert_eq!(BindArg::from_json(&t, &arr).unwrap(), BindArg::TextArray(vec!["a".to_string(), "b".to_string()]));
+        let arr = BindArg::array(&[true, false]);
+        assert_eq!(BindArg::from_json(&t, &arr).unwrap(), BindArg::BoolArray(vec![true, false]));
+        let arr = BindArg::array(&[serde_json::json!(1), serde_json::json!("a"), serde_json::json!(null)]);
+        assert!(BindArg::from_json(&t, &arr).is_err());
+        let obj = serde_json::json!({"a": 1});
+        assert_eq!(BindArg::from_json(&t, &obj).unwrap(), BindArg::Json(&obj));
+        let time = NaiveDateTime::parse_from_str("2020-02-09 01:02:03.5", "%Y-%m-%d %H:%M:%S%.f").unwrap();
+        assert_eq!(BindArg::from_json(&t, &BindArg::timestamp(&time)).unwrap(), BindArg::DateTime(time));
+        assert!(BindArg::from_json(&t, &serde_json::json!({TIMESTAMP_ARG: "2020"})).is_err());
+    }
+
+    #[test]
+    fn test_bind_arg_sqlite() {
+        let t = DriverType::Sqlite;
+        let big = serde_json::json!(u64::MAX);
+        assert_eq!(BindArg::from_json(&t, &big).unwrap(), BindArg::Text(Cow::Owned(u64::MAX.to_string())));
+        let n = serde_json::json!(i64::MIN);
+        assert_eq!(BindArg::from_json(&t, &n).unwrap(), BindArg::I64(i64::MIN));
+        let arr = serde_json::json!([1, 2]);
+        assert_eq!(BindArg::from_json(&t, &arr).unwrap(), BindArg::Json(&arr));
+        assert_eq!(BindArg::from_json(&t, &BindArg::array(&[1, 2])).unwrap(), BindArg::Json(&arr));
+        let time = NaiveDateTime::parse_from_str("2020-02-09 01:02:03", "%Y-%m-%d %H:%M:%S").unwrap();
+        assert_eq!(BindArg::from_json(&t, &BindArg::timestamp(&time)).unwrap(), BindArg::Text(Cow::Borrowed("2020-02-09 01:02:03")));
+        assert!(BindArg::from_json(&DriverType::None, &arr).is_err());
+    }
+
+    #[test]
+    fn test_bind_decimal_sqlite() {
+        async_std::task::block_on(async {
+            let path = std::env::temp_dir().join(format!("rbatis_core_{}.db", std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_nanos()));
+            std::fs::File::create(&path).unwrap();
+            let pool = DBPool::new(&format!("sqlite://{}", path.to_str().unwrap())).await.unwrap();
+            let mut conn = pool.acquire().await.unwrap();
+            let d = "12345678901234567890.123456789";
+            let time = NaiveDateTime::parse_from_str("2020-02-09 01:02:03", "%Y-%m-%d %H:%M:%S").unwrap();
+            let mut q = DBQuery::new(&DriverType::Sqlite, "SELECT ? AS d, ? AS t").unwrap();
+            q.bind_arg(BindArg::Decimal(BigDecimal::from_str(d).unwrap())).unwrap();
+            q.bind_arg(BindArg::DateTime(time)).unwrap();
+            let v: serde_json::Value = conn.fetch_parperd(q).unwrap().decode_json().await.unwrap();
+            assert_eq!(v, serde_json::json!([{"d": d, "t": "2020-02-09 01:02:03"}]));
+            drop(conn);
+            drop(pool);
+            let path = path.to_str().unwrap();
+            for file in &[path.to_string(), format!("{}-wal", path), format!("{}-shm", path)] {
+                let _ = std::fs::remove_file(file);
+            }
+        });
+    }
+
+    #[test]
+    fn test_bind_value() {
+        let args = vec![serde_json::json!(null), serde_json::json!(true), serde_json::json!(u64::MAX), serde_json::json!(-1),
+                        serde_json::json!(1.5), serde_json::json!("a"), serde_json::json!([1, 2]), serde_json::json!(["a"]), serde_json::json!({"a": 1}), BindArg::array(&[1, 2]), BindArg::timestamp(&NaiveDateTime::from_timestamp(0, 0))];
+        for t in &[DriverType::Mysql, DriverType::Postgres, DriverType::Sqlite] {
+            let mut q = DBQuery::new(t, "select 1").unwrap();
+            for arg in &args {
+                q.bind_value(arg).unwrap();
+            }
+        }
+    }
+}
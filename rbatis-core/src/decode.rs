@@ -107,3 +107,302 @@ fn is_array(type_name: &str) -> bool {
     }
     return false;
 }
+
+/// the typed value of an column,the number/string/bool decode into T without the json value
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColumnValue {
+    Null,
+    Bool(bool),
+    I64(i64),
+    U64(u64),
+    F64(f64),
+    /// text,and the decimal(keep the precision)
+    String(String),
+    /// date,time,point...
+    Json(serde_json::Value),
+}
+
+impl ColumnValue {
+    /// the value which serialize into json,for example: chrono::NaiveDate
+    pub fn to_json<T>(v: &T) -> ColumnValue where T: serde::Serialize {
+        ColumnValue::Json(serde_json::to_value(v).unwrap_or(serde_json::Value::Null))
+    }
+
+    pub fn into_json(self) -> serde_json::Value {
+        match self {
+            ColumnValue::Null => serde_json::Value::Null,
+            ColumnValue::Bool(b) => serde_json::Value::from(b),
+            ColumnValue::I64(n) => serde_json::Value::from(n),
+            ColumnValue::U64(n) => serde_json::Value::from(n),
+            ColumnValue::F64(n) => serde_json::Value::from(n),
+            ColumnValue::String(s) => serde_json::Value::from(s),
+            ColumnValue::Json(v) => v,
+        }
+    }
+
+    fn is_null(&self) -> bool {
+        match self {
+            ColumnValue::Null | ColumnValue::Json(serde_json::Value::Null) => true,
+            _ => false,
+        }
+    }
+}
+
+/// the row which can decode column by column,see row_decode()
+pub trait RowColumns {
+    /// column count
+    fn column_len(&self) -> usize;
+    /// column name of index
+    fn column_name(&self, index: usize) -> crate::Result<String>;
+    /// column value of index
+    fn column_value(&self, index: usize) -> crate::Result<ColumnValue>;
+}
+
+/// decode an row into T directly(without build the row json object)
+/// struct/map: decode by column name
+/// tuple/Vec: decode by column index
+/// i64,u64,String,Option<T>...: the row must have only one column
+pub fn row_decode<T, R>(row: &R) -> Result<T, crate::Error>
+    where T: DeserializeOwned, R: RowColumns {
+    let r = T::deserialize(RowDeserializer { row });
+    match r {
+        Ok(v) => Ok(v),
+        Err(e) => Err(decode_err!("[rbatis] row decode: {}, fail:{}", std::any::type_name::<T>(), e))
+    }
+}
+
+struct RowDeserializer<'r, R> where R: RowColumns {
+    row: &'r R,
+}
+
+impl<'r, R> RowDeserializer<'r, R> where R: RowColumns {
+    /// the value of the only one column
+    fn single_value(&self) -> Result<ColumnValue, serde_json::Error> {
+        if self.row.column_len() != 1 {
+            return Err(serde::de::Error::custom(format!("row have {} columns,but decode one column", self.row.column_len())));
+        }
+        self.column_value(0)
+    }
+
+    fn column_value(&self, index: usize) -> Result<ColumnValue, serde_json::Error> {
+        self.row.column_value(index).map_err(|e| serde::de::Error::custom(e.to_string()))
+    }
+}
+
+macro_rules! forward_to_single_column {
+    ($($method:ident)*) => {
+        $(
+            fn $method<V>(self, visitor: V) -> Result<V::Value, Self::Error> where V: serde::de::Visitor<'de> {
+                ColumnDeserializer(self.single_value()?).$method(visitor)
+            }
+        )*
+    };
+}
+
+impl<'de, 'r, R> serde::Deserializer<'de> for RowDeserializer<'r, R> where R: RowColumns {
+    type Error = serde_json::Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error> where V: serde::de::Visitor<'de> {
+        self.deserialize_map(visitor)
+    }
+
+    forward_to_single_column! {
+        deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64
+        deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64 deserialize_f32 deserialize_f64
+        deserialize_char deserialize_bytes deserialize_byte_buf deserialize_unit deserialize_identifier
+    }
+
+    fn deserialize_str<V>(self, visitor: V) -> Result<V::Value, Self::Error> where V: serde::de::Visitor<'de> {
+        self.deserialize_string(visitor)
+    }
+
+    fn deserialize_string<V>(self, visitor: V) -> Result<V::Value, Self::Error> where V: serde::de::Visitor<'de> {
+        match self.single_value()? {
+            ColumnValue::String(s) => visitor.visit_string(s),
+            //number,bool... decode as string
+            v => visitor.visit_string(v.into_json().to_string())
+        }
+    }
+
+    /// Option<T>: None if the row is empty,or the only one column is null.
+    /// the row have many columns is always Some,the null column decode into the field of T
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error> where V: serde::de::Visitor<'de> {
+        if self.row.column_len() == 0 {
+            return visitor.visit_none();
+        }
+        if self.row.column_len() == 1 && self.column_value(0)?.is_null() {
+            return visitor.visit_none();
+        }
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_unit_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value, Self::Error> where V: serde::de::Visitor<'de> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value, Self::Error> where V: serde::de::Visitor<'de> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, Self::Error> where V: serde::de::Visitor<'de> {
+        visitor.visit_seq(RowAccess { de: self, index: 0 })
+    }
+
+    fn deserialize_tuple<V>(self, _len: usize, visitor: V) -> Result<V::Value, Self::Error> where V: serde::de::Visitor<'de> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V>(self, _name: &'static str, _len: usize, visitor: V) -> Result<V::Value, Self::Error> where V: serde::de::Visitor<'de> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V>(self, visitor: V) -> Result<V::Value, Self::Error> where V: serde::de::Visitor<'de> {
+        visitor.visit_map(RowAccess { de: self, index: 0 })
+    }
+
+    fn deserialize_struct<V>(self, _name: &'static str, _fields: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error> where V: serde::de::Visitor<'de> {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_enum<V>(self, name: &'static str, variants: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error> where V: serde::de::Visitor<'de> {
+        ColumnDeserializer(self.single_value()?).deserialize_enum(name, variants, visitor)
+    }
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value, Self::Error> where V: serde::de::Visitor<'de> {
+        visitor.visit_unit()
+    }
+}
+
+/// access the row columns by index
+struct RowAccess<'r, R> where R: RowColumns {
+    de: RowDeserializer<'r, R>,
+    index: usize,
+}
+
+impl<'de, 'r, R> serde::de::SeqAccess<'de> for RowAccess<'r, R> where R: RowColumns {
+    type Error = serde_json::Error;
+
+    fn next_element_seed<S>(&mut self, seed: S) -> Result<Option<S::Value>, Self::Error> where S: serde::de::DeserializeSeed<'de> {
+        if self.index >= self.de.row.column_len() {
+            return Ok(None);
+        }
+        let v = self.de.column_value(self.index)?;
+        self.index += 1;
+        seed.deserialize(ColumnDeserializer(v)).map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.de.row.column_len() - self.index)
+    }
+}
+
+impl<'de, 'r, R> serde::de::MapAccess<'de> for RowAccess<'r, R> where R: RowColumns {
+    type Error = serde_json::Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error> where K: serde::de::DeserializeSeed<'de> {
+        if self.index >= self.de.row.column_len() {
+            return Ok(None);
+        }
+        let name = self.de.row.column_name(self.index).map_err(|e| serde::de::Error::custom(e.to_string()))?;
+        seed.deserialize(serde::de::IntoDeserializer::<serde_json::Error>::into_deserializer(name)).map(Some)
+    }
+
+    fn next_value_seed<S>(&mut self, seed: S) -> Result<S::Value, Self::Error> where S: serde::de::DeserializeSeed<'de> {
+        let v = self.de.column_value(self.index)?;
+        self.index += 1;
+        seed.deserialize(ColumnDeserializer(v))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.de.row.column_len() - self.index)
+    }
+}
+
+/// decode one column value
+struct ColumnDeserializer(ColumnValue);
+
+impl<'de> serde::Deserializer<'de> for ColumnDeserializer {
+    type Error = serde_json::Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error> where V: serde::de::Visitor<'de> {
+        match self.0 {
+            ColumnValue::Null => visitor.visit_unit(),
+            ColumnValue::Bool(b) => visitor.visit_bool(b),
+            ColumnValue::I64(n) => visitor.visit_i64(n),
+            ColumnValue::U64(n) => visitor.visit_u64(n),
+            ColumnValue::F64(n) => visitor.visit_f64(n),
+            ColumnValue::String(s) => visitor.visit_string(s),
+            ColumnValue::Json(v) => v.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error> where V: serde::de::Visitor<'de> {
+        match self.0.is_null() {
+            true => visitor.visit_none(),
+            false => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value, Self::Error> where V: serde::de::Visitor<'de> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V>(self, name: &'static str, variants: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error> where V: serde::de::Visitor<'de> {
+        self.0.into_json().deserialize_enum(name, variants, visitor)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct identifier ignored_any
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde::{Deserialize, Serialize};
+
+    use crate::decode::{ColumnValue, row_decode, RowColumns};
+
+    /// an row of (name,value) for test
+    struct TestRow(Vec<(&'static str, ColumnValue)>);
+
+    impl RowColumns for TestRow {
+        fn column_len(&self) -> usize {
+            self.0.len()
+        }
+
+        fn column_name(&self, index: usize) -> crate::Result<String> {
+            Ok(self.0[index].0.to_string())
+        }
+
+        fn column_value(&self, index: usize) -> crate::Result<ColumnValue> {
+            Ok(self.0[index].1.clone())
+        }
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Account {
+        id: u64,
+        name: Option<String>,
+        amount: String,
+    }
+
+    #[test]
+    fn test_row_decode() {
+        let row = TestRow(vec![("id", ColumnValue::U64(u64::MAX)), ("name", ColumnValue::Null), ("amount", ColumnValue::String("12345678901234567890.123456789".to_string()))]);
+        let account: Account = row_decode(&row).unwrap();
+        assert_eq!(account, Account { id: u64::MAX, name: None, amount: "12345678901234567890.123456789".to_string() });
+        //the null column not make the row None
+        let account: Option<Account> = row_decode(&row).unwrap();
+        assert_eq!(account, Some(Account { id: u64::MAX, name: None, amount: "12345678901234567890.123456789".to_string() }));
+        let tuple: (u64, Option<String>, String) = row_decode(&row).unwrap();
+        assert_eq!(tuple.0, u64::MAX);
+
+        let count = TestRow(vec![("count(1)", ColumnValue::I64(10))]);
+        assert_eq!(row_decode::<u64, _>(&count).unwrap(), 10);
+        assert_eq!(row_decode::<String, _>(&count).unwrap(), "10");
+        let null = TestRow(vec![("max(id)", ColumnValue::Null)]);
+        assert_eq!(row_decode::<Option<i64>, _>(&null).unwrap(), None);
+        assert!(row_decode::<i64, _>(&row).is_err());
+    }
+}
@@ -76,6 +76,9 @@ pub enum Error {
 
     /// An error occurred decoding data received from the database.
     Decode(Box<dyn StdError + Send + Sync>),
+
+    /// An optimistic lock update matched no row, the version of the data was changed by others.
+    StaleUpdate(String),
 }
 
 impl Error {
@@ -158,6 +161,8 @@ impl Display for Error {
             Error::PoolClosed => f.write_str("attempted to acquire a connection on a closed pool"),
 
             Error::Tls(ref err) => write!(f, "error during TLS upgrade: {}", err),
+
+            Error::StaleUpdate(ref err) => f.write_str(err),
         }
     }
 }
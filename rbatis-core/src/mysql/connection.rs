@@ -87,6 +87,9 @@ pub struct MySqlConnection {
     pub(super) is_ready: bool,
     pub(super) cache_statement: HashMap<Box<str>, u32>,
 
+    // The last_insert_id of the OK packet which returned by the last execute
+    pub(super) last_insert_id: u64,
+
     // Work buffer for the value ranges of the current row
     // This is used as the backing memory for each Row's value indexes
     pub(super) current_row_values: Vec<Option<Range<usize>>>,
@@ -283,6 +286,7 @@ impl MySqlConnection {
             current_row_values: Vec::with_capacity(10),
             is_ready: true,
             cache_statement: HashMap::new(),
+            last_insert_id: 0,
         };
 
         // After the connection is established, we initialize by configuring a few
@@ -10,12 +10,13 @@ use crate::executor::Execute;
 use crate::mysql::{MySql, MySqlArguments, MySqlConnection, MySqlRow, MySqlTypeInfo};
 use crate::mysql::protocol::{ColumnCount, ColumnDefinition, Row, Status};
 use crate::pool::Pool;
-use crate::decode::json_decode;
+use crate::decode::{json_decode, row_decode};
 
 pub struct MySqlCursor<'c, 'q> {
     source: ConnectionSource<'c, MySqlConnection>,
     query: Option<(&'q str, Option<MySqlArguments>)>,
     column_names: Arc<HashMap<Box<str>, u16>>,
+    column_index_names: Arc<Vec<Box<str>>>,
     column_types: Vec<MySqlTypeInfo>,
     binary: bool,
 }
@@ -34,6 +35,7 @@ impl<'c, 'q> Cursor<'c, 'q> for MySqlCursor<'c, 'q> {
         Self {
             source: ConnectionSource::Pool(pool.clone()),
             column_names: Arc::default(),
+            column_index_names: Arc::default(),
             column_types: Vec::new(),
             binary: true,
             query: Some(query.into_parts()),
@@ -49,6 +51,7 @@ impl<'c, 'q> Cursor<'c, 'q> for MySqlCursor<'c, 'q> {
         Self {
             source: ConnectionSource::ConnectionRef(conn),
             column_names: Arc::default(),
+            column_index_names: Arc::default(),
             column_types: Vec::new(),
             binary: true,
             query: Some(query.into_parts()),
@@ -71,20 +74,53 @@ impl<'c, 'q> Cursor<'c, 'q> for MySqlCursor<'c, 'q> {
     fn fetch_json(&mut self) -> BoxFuture<Result<Vec<serde_json::Value>, crate::Error>> {
         Box::pin(async move {
             let mut arr = vec![];
-            while let Some(row) = self.next().await? as Option<MySqlRow<'_>> {
-                let mut m = serde_json::Map::new();
-                let keys = row.names.keys();
-                for x in keys {
-                    let key = x.to_string();
-                    let key_str=key.as_str();
-                    let v:serde_json::Value = row.json_decode_impl(key_str)?;
-                    m.insert(key, v);
-                }
-                arr.push(serde_json::Value::Object(m));
+            while let Some(v) = self.fetch_json_next().await? {
+                arr.push(v);
             }
             return Ok(arr);
         })
     }
+
+    fn fetch_json_next(&mut self) -> BoxFuture<Result<Option<serde_json::Value>, crate::Error>> {
+        Box::pin(async move {
+            let row = self.next().await? as Option<MySqlRow<'_>>;
+            if row.is_none() {
+                return Ok(None);
+            }
+            let row = row.unwrap();
+            let mut m = serde_json::Map::new();
+            let keys = row.names.keys();
+            for x in keys {
+                let key = x.to_string();
+                let key_str=key.as_str();
+                let v:serde_json::Value = row.json_decode_impl(key_str)?;
+                m.insert(key, v);
+            }
+            return Ok(Some(serde_json::Value::Object(m)));
+        })
+    }
+
+    fn fetch_decode<T>(&mut self) -> BoxFuture<Result<Vec<T>, crate::Error>>
+        where T: DeserializeOwned + Send {
+        Box::pin(async move {
+            let mut arr = vec![];
+            while let Some(v) = self.fetch_decode_next().await? {
+                arr.push(v);
+            }
+            return Ok(arr);
+        })
+    }
+
+    fn fetch_decode_next<T>(&mut self) -> BoxFuture<Result<Option<T>, crate::Error>>
+        where T: DeserializeOwned + Send {
+        Box::pin(async move {
+            let row = self.next().await? as Option<MySqlRow<'_>>;
+            match row {
+                Some(row) => Ok(Some(row_decode(&row)?)),
+                None => Ok(None)
+            }
+        })
+    }
 }
 
 
@@ -149,6 +185,7 @@ async fn next<'a, 'c: 'a, 'q: 'a>(
                 cursor.column_types.reserve(cc.columns as usize);
 
                 let mut column_names = HashMap::with_capacity(cc.columns as usize);
+                let mut column_index_names = Vec::with_capacity(cc.columns as usize);
 
                 for i in 0..cc.columns {
                     let column = ColumnDefinition::read(conn.stream.receive().await?)?;
@@ -160,6 +197,7 @@ async fn next<'a, 'c: 'a, 'q: 'a>(
                     if let Some(name) = column.name() {
                         column_names.insert(name.to_owned().into_boxed_str(), i as u16);
                     }
+                    column_index_names.push(column.name().unwrap_or_default().to_owned().into_boxed_str());
                 }
 
                 if cc.columns > 0 {
@@ -167,6 +205,7 @@ async fn next<'a, 'c: 'a, 'q: 'a>(
                 }
 
                 cursor.column_names = Arc::new(column_names);
+                cursor.column_index_names = Arc::new(column_index_names);
                 initial = false;
             }
 
@@ -181,6 +220,7 @@ async fn next<'a, 'c: 'a, 'q: 'a>(
                 let row = MySqlRow {
                     row,
                     names: Arc::clone(&cursor.column_names),
+                    index_names: Arc::clone(&cursor.column_index_names),
                 };
 
                 return Ok(Some(row));
@@ -110,6 +110,7 @@ impl super::MySqlConnection {
                         let ok = self.stream.handle_ok()?;
 
                         rows += ok.affected_rows;
+                        self.last_insert_id = ok.last_insert_id;
                         ok.status
                     };
 
@@ -130,6 +131,12 @@ impl super::MySqlConnection {
         Ok(rows)
     }
 
+    /// Returns the `LAST_INSERT_ID` of the OK packet, which is returned by the last `execute`.
+    /// For a multiple-row insert, it is the id of the first inserted row.
+    pub fn last_insert_id(&self) -> u64 {
+        self.last_insert_id
+    }
+
     // method is not named describe to work around an intellijrust bug
     // otherwise it marks someone trying to describe the connection as "method is private"
     async fn do_describe(&mut self, query: &str) -> crate::Result<Describe<MySql>> {
@@ -1,15 +1,19 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
+use crate::decode::{ColumnValue, RowColumns};
 use crate::mysql::protocol;
 use crate::mysql::{MySql, MySqlValue};
 use crate::row::{ColumnIndex, Row};
+use crate::value::RawValue;
 use serde::de::DeserializeOwned;
 
 #[derive(Debug)]
 pub struct MySqlRow<'c> {
     pub(super) row: protocol::Row<'c>,
     pub(super) names: Arc<HashMap<Box<str>, u16>>,
+    /// the column names by index
+    pub(super) index_names: Arc<Vec<Box<str>>>,
 }
 
 impl<'c> MySqlRow<'c> {
@@ -24,6 +28,23 @@ impl<'c> MySqlRow<'c> {
 
 impl crate::row::private_row::Sealed for MySqlRow<'_> {}
 
+impl<'c> RowColumns for MySqlRow<'c> {
+    fn column_len(&self) -> usize {
+        self.row.len()
+    }
+
+    fn column_name(&self, index: usize) -> crate::Result<String> {
+        match self.index_names.get(index) {
+            Some(name) => Ok(name.to_string()),
+            None => Err(crate::Error::ColumnIndexOutOfBounds { len: self.row.len(), index })
+        }
+    }
+
+    fn column_value(&self, index: usize) -> crate::Result<ColumnValue> {
+        self.try_get_raw(index)?.try_to_column()
+    }
+}
+
 impl<'c> Row<'c> for MySqlRow<'c> {
     type Database = MySql;
 
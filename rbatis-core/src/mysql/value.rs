@@ -1,7 +1,7 @@
 use crate::error::UnexpectedNullError;
 use crate::mysql::{MySql, MySqlTypeInfo};
 use crate::value::RawValue;
-use crate::decode::Decode;
+use crate::decode::{ColumnValue, Decode};
 use crate::types::BigDecimal;
 use crate::Result;
 
@@ -18,6 +18,10 @@ pub struct MySqlValue<'c> {
 }
 
 impl<'c> MySqlValue<'c> {
+    fn decode<T>(&self) -> Result<T> where T: Decode<'c, MySql> {
+        Decode::<'c, MySql>::decode(self.clone())
+    }
+
     /// Gets the binary or text data for this value; or, `UnexpectedNullError` if this
     /// is a `NULL` value.
     pub(crate) fn try_get(&self) -> crate::Result<MySqlData<'c>> {
@@ -63,131 +67,30 @@ impl<'c> RawValue<'c> for MySqlValue<'c> {
         self.type_info.clone()
     }
 
-    fn try_to_json(&self) -> Result<serde_json::Value> {
+    fn try_to_column(&self) -> Result<ColumnValue> {
         if self.type_info.is_none() {
-            return Ok(serde_json::Value::Null);
+            return Ok(ColumnValue::Null);
         }
         //TODO batter way to match type replace use string match
         let type_string = format!("{}", self.type_info.as_ref().unwrap());
         match type_string.as_str() {
-            "NULL" => return Ok(serde_json::Value::Null),
-            "NEWDECIMAL" => {
-                let r:crate::Result<BigDecimal> = Decode::<'_,MySql>::decode(self.clone());
-                if r.is_err() {
-                    return Err(r.err().unwrap());
-                }
-                return Ok(serde_json::Value::from(r.unwrap().to_string()));
-            }
-            "BIGINT UNSIGNED" => {
-                let r:crate::Result<u64> = Decode::<'_,MySql>::decode(self.clone());
-                if r.is_err() {
-                    return Err(r.err().unwrap());
-                }
-                return Ok(serde_json::Value::from(r.unwrap()));
-            }
-            "BIGINT" => {
-                let r:crate::Result<i64> = Decode::<'_,MySql>::decode(self.clone());
-                if r.is_err() {
-                    return Err(r.err().unwrap());
-                }
-                return Ok(serde_json::Value::from(r.unwrap()));
-            }
-            "INT UNSIGNED" => {
-                let r:crate::Result<u32> = Decode::<'_,MySql>::decode(self.clone());
-                if r.is_err() {
-                    return Err(r.err().unwrap());
-                }
-                return Ok(serde_json::Value::from(r.unwrap()));
-            }
-            "INT" => {
-                let r:crate::Result<i32> = Decode::<'_,MySql>::decode(self.clone());
-                if r.is_err() {
-                    return Err(r.err().unwrap());
-                }
-                return Ok(serde_json::Value::from(r.unwrap()));
-            }
-            "SMALLINT" => {
-                let r:crate::Result<i16> = Decode::<'_,MySql>::decode(self.clone());
-                if r.is_err() {
-                    return Err(r.err().unwrap());
-                }
-                return Ok(serde_json::Value::from(r.unwrap()));
-            }
-            "SMALLINT UNSIGNED" => {
-                let r:crate::Result<u16> = Decode::<'_,MySql>::decode(self.clone());
-                if r.is_err() {
-                    return Err(r.err().unwrap());
-                }
-                return Ok(serde_json::Value::from(r.unwrap()));
-            }
-            "TINYINT UNSIGNED" => {
-                let r:crate::Result<u8> = Decode::<'_,MySql>::decode(self.clone());
-                if r.is_err() {
-                    return Err(r.err().unwrap());
-                }
-                return Ok(serde_json::Value::from(r.unwrap()));
-            }
-            "TINYINT" => {
-                let r:crate::Result<i8> = Decode::<'_,MySql>::decode(self.clone());
-                if r.is_err() {
-                    return Err(r.err().unwrap());
-                }
-                return Ok(serde_json::Value::from(r.unwrap()));
-            }
-            "FLOAT" => {
-                let r:crate::Result<f32> = Decode::<'_,MySql>::decode(self.clone());
-                if r.is_err() {
-                    return Err(r.err().unwrap());
-                }
-                return Ok(serde_json::Value::from(r.unwrap()));
-            }
-            "DOUBLE" => {
-                let r:crate::Result<f64> = Decode::<'_,MySql>::decode(self.clone());
-                if r.is_err() {
-                    return Err(r.err().unwrap());
-                }
-                return Ok(serde_json::Value::from(r.unwrap()));
-            }
-            "BINARY" | "VARBINARY" | "BLOB" | "CHAR" | "VARCHAR" | "TEXT" => {
-                let r:crate::Result<String> = Decode::<'_,MySql>::decode(self.clone());
-                if r.is_err() {
-                    return Err(r.err().unwrap());
-                }
-                return Ok(serde_json::Value::from(r.unwrap()));
-            }
-            "DATE" => {
-                let r:crate::Result<chrono::NaiveDate> = Decode::<'_,MySql>::decode(self.clone());
-                if r.is_err() {
-                    return Err(r.err().unwrap());
-                }
-                let t=serde_json::to_value(&r.unwrap());
-                return Ok(t.unwrap_or(serde_json::Value::Null));
-            }
-            "TIME"  => {
-                let r:crate::Result<chrono::NaiveTime> = Decode::<'_,MySql>::decode(self.clone());
-                if r.is_err() {
-                    return Err(r.err().unwrap());
-                }
-                let t=serde_json::to_value(&r.unwrap());
-                return Ok(t.unwrap_or(serde_json::Value::Null));
-            }
-            "DATETIME"  => {
-                let r:crate::Result<chrono::NaiveDateTime> = Decode::<'_,MySql>::decode(self.clone());
-                if r.is_err() {
-                    return Err(r.err().unwrap());
-                }
-                let t=serde_json::to_value(&r.unwrap());
-                return Ok(t.unwrap_or(serde_json::Value::Null));
-            }
-            "TIMESTAMP" => {
-                let r:crate::Result<chrono::NaiveDateTime> = Decode::<'_,MySql>::decode(self.clone());
-                if r.is_err() {
-                    return Err(r.err().unwrap());
-                }
-                let t=serde_json::to_value(&r.unwrap());
-                return Ok(t.unwrap_or(serde_json::Value::Null));
-            }
-            _ => return Err(crate::Error::from(format!("un support database type for:{:?}!", type_string))),
+            "NULL" => Ok(ColumnValue::Null),
+            "NEWDECIMAL" => Ok(ColumnValue::String(self.decode::<BigDecimal>()?.to_string())),
+            "BIGINT UNSIGNED" => Ok(ColumnValue::U64(self.decode::<u64>()?)),
+            "BIGINT" => Ok(ColumnValue::I64(self.decode::<i64>()?)),
+            "INT UNSIGNED" => Ok(ColumnValue::U64(self.decode::<u32>()? as u64)),
+            "INT" => Ok(ColumnValue::I64(self.decode::<i32>()? as i64)),
+            "SMALLINT" => Ok(ColumnValue::I64(self.decode::<i16>()? as i64)),
+            "SMALLINT UNSIGNED" => Ok(ColumnValue::U64(self.decode::<u16>()? as u64)),
+            "TINYINT UNSIGNED" => Ok(ColumnValue::U64(self.decode::<u8>()? as u64)),
+            "TINYINT" => Ok(ColumnValue::I64(self.decode::<i8>()? as i64)),
+            "FLOAT" => Ok(ColumnValue::F64(self.decode::<f32>()? as f64)),
+            "DOUBLE" => Ok(ColumnValue::F64(self.decode::<f64>()?)),
+            "BINARY" | "VARBINARY" | "BLOB" | "CHAR" | "VARCHAR" | "TEXT" => Ok(ColumnValue::String(self.decode::<String>()?)),
+            "DATE" => Ok(ColumnValue::to_json(&self.decode::<chrono::NaiveDate>()?)),
+            "TIME" => Ok(ColumnValue::to_json(&self.decode::<chrono::NaiveTime>()?)),
+            "DATETIME" | "TIMESTAMP" => Ok(ColumnValue::to_json(&self.decode::<chrono::NaiveDateTime>()?)),
+            _ => Err(crate::Error::from(format!("un support database type for:{:?}!", type_string))),
         }
     }
 }
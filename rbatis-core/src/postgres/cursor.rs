@@ -4,7 +4,7 @@ use std::sync::Arc;
 
 use crate::connection::ConnectionSource;
 use crate::cursor::Cursor;
-use crate::decode::json_decode;
+use crate::decode::{json_decode, row_decode};
 use crate::executor::Execute;
 use crate::pool::Pool;
 use crate::postgres::{PgArguments, PgConnection, PgRow, Postgres};
@@ -64,19 +64,52 @@ impl<'c, 'q> Cursor<'c, 'q> for PgCursor<'c, 'q> {
     fn fetch_json(&mut self) -> BoxFuture<'_, Result<Vec<serde_json::Value>, crate::Error>> {
         Box::pin(async move {
             let mut arr = vec![];
-            while let Some(row) = self.next().await? as Option<PgRow<'_>> {
-                let mut m = serde_json::Map::new();
-                let keys = row.statement.names.keys();
-                for x in keys {
-                    let key = x.to_string();
-                    let v: serde_json::Value = row.json_decode_impl(key.as_str())?;
-                    m.insert(key, v);
-                }
-                arr.push(serde_json::Value::Object(m));
+            while let Some(v) = self.fetch_json_next().await? {
+                arr.push(v);
             }
             return Ok(arr);
         })
     }
+
+    fn fetch_json_next(&mut self) -> BoxFuture<'_, Result<Option<serde_json::Value>, crate::Error>> {
+        Box::pin(async move {
+            let row = self.next().await? as Option<PgRow<'_>>;
+            if row.is_none() {
+                return Ok(None);
+            }
+            let row = row.unwrap();
+            let mut m = serde_json::Map::new();
+            let keys = row.statement.names.keys();
+            for x in keys {
+                let key = x.to_string();
+                let v: serde_json::Value = row.json_decode_impl(key.as_str())?;
+                m.insert(key, v);
+            }
+            return Ok(Some(serde_json::Value::Object(m)));
+        })
+    }
+
+    fn fetch_decode<T>(&mut self) -> BoxFuture<Result<Vec<T>, crate::Error>>
+        where T: DeserializeOwned + Send {
+        Box::pin(async move {
+            let mut arr = vec![];
+            while let Some(v) = self.fetch_decode_next().await? {
+                arr.push(v);
+            }
+            return Ok(arr);
+        })
+    }
+
+    fn fetch_decode_next<T>(&mut self) -> BoxFuture<Result<Option<T>, crate::Error>>
+        where T: DeserializeOwned + Send {
+        Box::pin(async move {
+            let row = self.next().await? as Option<PgRow<'_>>;
+            match row {
+                Some(row) => Ok(Some(row_decode(&row)?)),
+                None => Ok(None)
+            }
+        })
+    }
 }
 
 
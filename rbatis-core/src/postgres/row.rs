@@ -1,11 +1,13 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
+use crate::decode::{ColumnValue, RowColumns};
 use crate::postgres::protocol::{DataRow, TypeFormat};
 use crate::postgres::type_info::SharedStr;
 use crate::postgres::value::PgValue;
 use crate::postgres::{PgTypeInfo, Postgres};
 use crate::row::{ColumnIndex, Row};
+use crate::value::RawValue;
 use serde::de::DeserializeOwned;
 
 // A statement has 0 or more columns being returned from the database
@@ -56,6 +58,24 @@ impl <'c>PgRow<'c>{
 
 impl crate::row::private_row::Sealed for PgRow<'_> {}
 
+impl<'c> RowColumns for PgRow<'c> {
+    fn column_len(&self) -> usize {
+        self.data.len()
+    }
+
+    fn column_name(&self, index: usize) -> crate::Result<String> {
+        match self.statement.columns.get(index) {
+            Some(Column { name: Some(name), .. }) => Ok(name.to_string()),
+            Some(_) => Err(decode_err!("column {} have no name", index)),
+            None => Err(crate::Error::ColumnIndexOutOfBounds { len: self.data.len(), index })
+        }
+    }
+
+    fn column_value(&self, index: usize) -> crate::Result<ColumnValue> {
+        self.try_get_raw(index)?.try_to_column()
+    }
+}
+
 impl<'c> Row<'c> for PgRow<'c> {
     type Database = Postgres;
 
@@ -1,7 +1,7 @@
 use std::str::from_utf8;
 use geo_types::{Point};
 
-use crate::decode::Decode;
+use crate::decode::{ColumnValue, Decode};
 use crate::error::UnexpectedNullError;
 use crate::postgres::{PgTypeInfo, Postgres};
 use crate::Result;
@@ -21,6 +21,10 @@ pub struct PgValue<'c> {
 }
 
 impl<'c> PgValue<'c> {
+    fn decode<T>(&self) -> Result<T> where T: Decode<'c, Postgres> {
+        Decode::<'c, Postgres>::decode(self.clone())
+    }
+
     /// Gets the binary or text data for this value; or, `UnexpectedNullError` if this
     /// is a `NULL` value.
     pub(crate) fn try_get(&self) -> crate::Result<PgData<'c>> {
@@ -87,122 +91,29 @@ impl<'c> RawValue<'c> for PgValue<'c> {
         }
     }
 
-    fn try_to_json(&self) -> Result<serde_json::Value> {
+    fn try_to_column(&self) -> Result<ColumnValue> {
         if self.type_info.is_none() {
-            return Ok(serde_json::Value::Null);
+            return Ok(ColumnValue::Null);
         }
         let type_string = format!("{}", self.type_info.as_ref().unwrap());
         match type_string.as_str() {
-            "NUMERIC" => {
-                //decimal
-                let r: crate::Result<BigDecimal> = Decode::<'_, Postgres>::decode(self.clone());
-                if r.is_err() {
-                    return Err(r.err().unwrap());
-                }
-                return Ok(serde_json::Value::from(r.unwrap().to_string()));
-            }
-            "BOOL" => {
-                let r: crate::Result<bool> = Decode::<'_, Postgres>::decode(self.clone());
-                if r.is_err() {
-                    return Err(r.err().unwrap());
-                }
-                return Ok(serde_json::Value::from(r.unwrap()));
-            }
+            //decimal
+            "NUMERIC" => Ok(ColumnValue::String(self.decode::<BigDecimal>()?.to_string())),
+            "BOOL" => Ok(ColumnValue::Bool(self.decode::<bool>()?)),
             "BYTEA" => {
                 unimplemented!();
             }
-            "FLOAT4" => {
-                let r: crate::Result<f32> = Decode::<'_, Postgres>::decode(self.clone());
-                if r.is_err() {
-                    return Err(r.err().unwrap());
-                }
-                return Ok(serde_json::Value::from(r.unwrap()));
-            }
-            "FLOAT8" => {
-                let r: crate::Result<f64> = Decode::<'_, Postgres>::decode(self.clone());
-                if r.is_err() {
-                    return Err(r.err().unwrap());
-                }
-                return Ok(serde_json::Value::from(r.unwrap()));
-            }
-            "INT2" => {
-                let r: crate::Result<i16> = Decode::<'_, Postgres>::decode(self.clone());
-                if r.is_err() {
-                    return Err(r.err().unwrap());
-                }
-                return Ok(serde_json::Value::from(r.unwrap()));
-            }
-            "INT4" => {
-                let r: crate::Result<i32> = Decode::<'_, Postgres>::decode(self.clone());
-                if r.is_err() {
-                    return Err(r.err().unwrap());
-                }
-                return Ok(serde_json::Value::from(r.unwrap()));
-            }
-            "INT8" => {
-                let r: crate::Result<i64> = Decode::<'_, Postgres>::decode(self.clone());
-                if r.is_err() {
-                    return Err(r.err().unwrap());
-                }
-                return Ok(serde_json::Value::from(r.unwrap()));
-            }
-            "TEXT" | "VARCHAR" | "BPCHAR" | "CHAR" => {
-                let r: crate::Result<String> = Decode::<'_, Postgres>::decode(self.clone());
-                if r.is_err() {
-                    return Err(r.err().unwrap());
-                }
-                return Ok(serde_json::Value::from(r.unwrap()));
-            }
-            "UUID" => {
-                let r: crate::Result<String> = Decode::<'_, Postgres>::decode(self.clone());
-                if r.is_err() {
-                    return Err(r.err().unwrap());
-                }
-                return Ok(serde_json::Value::from(r.unwrap()));
-            }
-
-            "TIME" => {
-                let r: crate::Result<chrono::NaiveTime> = Decode::<'_, Postgres>::decode(self.clone());
-                if r.is_err() {
-                    return Err(r.err().unwrap());
-                }
-                let t = serde_json::to_value(&r.unwrap());
-                return Ok(t.unwrap_or(serde_json::Value::Null));
-            }
-            "DATE" => {
-                let r: crate::Result<chrono::NaiveDate> = Decode::<'_, Postgres>::decode(self.clone());
-                if r.is_err() {
-                    return Err(r.err().unwrap());
-                }
-                let t = serde_json::to_value(&r.unwrap());
-                return Ok(t.unwrap_or(serde_json::Value::Null));
-            }
-            "TIMESTAMP" => {
-                let r: crate::Result<chrono::NaiveDateTime> = Decode::<'_, Postgres>::decode(self.clone());
-                if r.is_err() {
-                    return Err(r.err().unwrap());
-                }
-                let t = serde_json::to_value(&r.unwrap());
-                return Ok(t.unwrap_or(serde_json::Value::Null));
-            }
-            "TIMESTAMPTZ" => {
-                let r: crate::Result<chrono::NaiveDateTime> = Decode::<'_, Postgres>::decode(self.clone());
-                if r.is_err() {
-                    return Err(r.err().unwrap());
-                }
-                let t = serde_json::to_value(&r.unwrap());
-                return Ok(t.unwrap_or(serde_json::Value::Null));
-            }
-             "POINT" =>{
-                let r: crate::Result<Point<f64>> = Decode::<'_, Postgres>::decode(self.clone());
-                if r.is_err() {
-                    return Err(r.err().unwrap());
-                }
-                let t = serde_json::to_value(&r.unwrap());
-                return Ok(t.unwrap_or(serde_json::Value::Null));
-            }
-
-            _ => return Err(crate::Error::from(format!("un support database type for:{:?}!", type_string))),
+            "FLOAT4" => Ok(ColumnValue::F64(self.decode::<f32>()? as f64)),
+            "FLOAT8" => Ok(ColumnValue::F64(self.decode::<f64>()?)),
+            "INT2" => Ok(ColumnValue::I64(self.decode::<i16>()? as i64)),
+            "INT4" => Ok(ColumnValue::I64(self.decode::<i32>()? as i64)),
+            "INT8" => Ok(ColumnValue::I64(self.decode::<i64>()?)),
+            "TEXT" | "VARCHAR" | "BPCHAR" | "CHAR" | "UUID" => Ok(ColumnValue::String(self.decode::<String>()?)),
+            "TIME" => Ok(ColumnValue::to_json(&self.decode::<chrono::NaiveTime>()?)),
+            "DATE" => Ok(ColumnValue::to_json(&self.decode::<chrono::NaiveDate>()?)),
+            "TIMESTAMP" | "TIMESTAMPTZ" => Ok(ColumnValue::to_json(&self.decode::<chrono::NaiveDateTime>()?)),
+            "POINT" => Ok(ColumnValue::to_json(&self.decode::<Point<f64>>()?)),
+            _ => Err(crate::Error::from(format!("un support database type for:{:?}!", type_string))),
         }
     }
 }
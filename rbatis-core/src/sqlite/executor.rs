@@ -1,6 +1,6 @@
 use futures_core::future::BoxFuture;
 
-use libsqlite3_sys::sqlite3_changes;
+use libsqlite3_sys::{sqlite3_changes, sqlite3_last_insert_rowid};
 
 use crate::cursor::Cursor;
 use crate::describe::{Column, Describe};
@@ -64,6 +64,12 @@ impl SqliteConnection {
         changes as u64
     }
 
+    /// Returns the rowid of the most recent successful INSERT on this connection.
+    pub fn last_insert_rowid(&mut self) -> i64 {
+        // https://www.sqlite.org/c3ref/last_insert_rowid.html
+        unsafe { sqlite3_last_insert_rowid(self.handle()) }
+    }
+
     #[inline]
     pub(super) fn statement(&self, key: Option<usize>) -> &Statement {
         match key {
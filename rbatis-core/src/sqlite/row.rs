@@ -1,7 +1,9 @@
+use crate::decode::{ColumnValue, RowColumns};
 use crate::row::{ColumnIndex, Row};
 use crate::sqlite::statement::Statement;
 use crate::sqlite::value::SqliteValue;
 use crate::sqlite::{Sqlite, SqliteConnection};
+use crate::value::RawValue;
 use serde::de::DeserializeOwned;
 
 #[derive(Debug)]
@@ -37,6 +39,23 @@ impl <'c>SqliteRow<'c>{
     }
 }
 
+impl<'c> RowColumns for SqliteRow<'c> {
+    fn column_len(&self) -> usize {
+        self.column_keys.len()
+    }
+
+    fn column_name(&self, index: usize) -> crate::Result<String> {
+        match self.column_keys.get(index) {
+            Some(name) => Ok(name.clone()),
+            None => Err(crate::Error::ColumnIndexOutOfBounds { len: self.column_keys.len(), index })
+        }
+    }
+
+    fn column_value(&self, index: usize) -> crate::Result<ColumnValue> {
+        self.try_get_raw(index)?.try_to_column()
+    }
+}
+
 impl<'c> Row<'c> for SqliteRow<'c> {
     type Database = Sqlite;
 
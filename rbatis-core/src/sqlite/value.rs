@@ -13,7 +13,7 @@ use crate::sqlite::statement::Statement;
 use crate::sqlite::type_info::SqliteType;
 use crate::sqlite::{Sqlite, SqliteTypeInfo};
 use crate::value::RawValue;
-use crate::decode::Decode;
+use crate::decode::{ColumnValue, Decode};
 use crate::Result;
 
 #[derive(Debug,Clone)]
@@ -136,42 +136,17 @@ impl<'c> RawValue<'c> for SqliteValue<'c> {
         })
     }
 
-    fn try_to_json(&self) -> Result<serde_json::Value>  {
+    fn try_to_column(&self) -> Result<ColumnValue> {
         //TODO batter way to match type replace use string match
         let type_string = self.r#type();
         if type_string.is_none() {
-            return Ok(serde_json::Value::Null);
+            return Ok(ColumnValue::Null);
         }
-        let type_string = type_string.unwrap();
-        return match type_string {
-            SqliteType::Text => {
-                let r:crate::Result<String> = Decode::<'_,Sqlite>::decode(self.clone());
-                if r.is_err() {
-                    return Err(r.err().unwrap());
-                }
-                Ok(serde_json::Value::from(r.unwrap()))
-            },
-            SqliteType::Boolean => {
-                let r:crate::Result<bool> = Decode::<'_,Sqlite>::decode(self.clone());
-                if r.is_err() {
-                    return Err(r.err().unwrap());
-                }
-                Ok(serde_json::Value::from(r.unwrap()))
-            },
-            SqliteType::Integer => {
-                let r:crate::Result<i64> = Decode::<'_,Sqlite>::decode(self.clone());
-                if r.is_err() {
-                    return Err(r.err().unwrap());
-                }
-                Ok(serde_json::Value::from(r.unwrap()))
-            },
-            SqliteType::Float => {
-                let r:crate::Result<f64> = Decode::<'_,Sqlite>::decode(self.clone());
-                if r.is_err() {
-                    return Err(r.err().unwrap());
-                }
-                Ok(serde_json::Value::from(r.unwrap()))
-            },
+        match type_string.unwrap() {
+            SqliteType::Text => Ok(ColumnValue::String(Decode::<'_, Sqlite>::decode(self.clone())?)),
+            SqliteType::Boolean => Ok(ColumnValue::Bool(Decode::<'_, Sqlite>::decode(self.clone())?)),
+            SqliteType::Integer => Ok(ColumnValue::I64(Decode::<'_, Sqlite>::decode(self.clone())?)),
+            SqliteType::Float => Ok(ColumnValue::F64(Decode::<'_, Sqlite>::decode(self.clone())?)),
             SqliteType::Blob => {
                 unimplemented!()
             }
@@ -1,6 +1,7 @@
 use crate::database::Database;
 use bitflags::_core::fmt::Debug;
 use crate::Result;
+use crate::decode::ColumnValue;
 use crate::types::chrono::NaiveDateTime;
 use chrono::Local;
 
@@ -25,8 +26,13 @@ pub trait RawValue<'c> {
 
     fn type_info(&self) -> Option<<Self::Database as Database>::TypeInfo>;
 
+    /// to an typed column value,the number/string/bool will not convert into json
+    fn try_to_column(&self) -> Result<ColumnValue>;
+
     /// to an json value
-    fn try_to_json(&self) -> Result<serde_json::Value>;
+    fn try_to_json(&self) -> Result<serde_json::Value> {
+        Ok(self.try_to_column()?.into_json())
+    }
 }
 
 
@@ -9,7 +9,7 @@ mod sql;
 mod string_util;
 mod py_sql;
 
-#[proc_macro_derive(CRUDEnable)]
+#[proc_macro_derive(CRUDEnable, attributes(rbatis))]
 pub fn hello_macro_derive(input: TokenStream) -> TokenStream {
     // 构建 Rust 代码所代表的语法树
     // 以便可以进行操作
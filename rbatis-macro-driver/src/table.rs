@@ -6,9 +6,10 @@ use syn::{AttributeArgs, Data, FnArg, ItemFn, parse_macro_input, ReturnType};
 
 use crate::proc_macro::TokenStream;
 
-///filter id_type
-fn find_id_type_ident(arg: &syn::Data) -> Ident {
-    let mut id_type = Ident::new("String", Span::call_site());
+///filter id fields,the fields marked by #[rbatis(id)] or the field named "id"
+fn find_id_fields(arg: &syn::Data) -> Vec<&syn::Field> {
+    let mut id_fields = vec![];
+    let mut default_id_field = None;
     match &arg {
         syn::Data::Struct(ref data_struct) => match data_struct.fields {
             // field: (0) a: String
@@ -16,16 +17,13 @@ fn find_id_type_ident(arg: &syn::Data) -> Ident {
                 for (_, field) in fields_named.named.iter().enumerate() {
                     //println!("named struct field: ({}) {}: {}", index, field_name, field.ty.to_token_stream());
                     let field_name = format!("{}", field.ident.to_token_stream());
-                    if field_name.eq("id") {
-                        let ty = format!("{}", field.ty.to_token_stream());
-                        let mut inner_type = ty.trim().replace(" ", "").to_string();
-                        if inner_type.starts_with("Option<") {
-                            inner_type = inner_type.trim_start_matches("Option<").trim_end_matches(">").to_string();
-                        }
-                        //println!("id_type from:{}", &inner_type);
-                        id_type = Ident::new(inner_type.as_str(), Span::call_site());
-                        //println!("id_type:{}", &id_type);
-                        break;
+                    if has_rbatis_flag(&field.attrs, "skip") {
+                        continue;
+                    }
+                    if has_rbatis_flag(&field.attrs, "id") {
+                        id_fields.push(field);
+                    } else if field_name.eq("id") {
+                        default_id_field = Some(field);
                     }
                 }
             }
@@ -34,14 +32,140 @@ fn find_id_type_ident(arg: &syn::Data) -> Ident {
         },
         _ => (),
     }
-    id_type
+    if id_fields.is_empty() && default_id_field.is_some() {
+        id_fields.push(default_id_field.unwrap());
+    }
+    id_fields
+}
+
+///filter id_type, Option<T> => T, composite id => (T1,T2...)
+fn find_id_type(id_fields: &Vec<&syn::Field>) -> proc_macro2::TokenStream {
+    if id_fields.is_empty() {
+        return quote! { String };
+    }
+    let mut types = vec![];
+    for field in id_fields {
+        types.push(option_inner_type(&field.ty));
+    }
+    if types.len() == 1 {
+        let ty = types[0];
+        return quote! { #ty };
+    }
+    quote! { (#(#types),*) }
+}
+
+///Option<T> => T
+fn option_inner_type(ty: &syn::Type) -> &syn::Type {
+    if let syn::Type::Path(type_path) = ty {
+        if let Some(segment) = type_path.path.segments.last() {
+            if segment.ident == "Option" {
+                if let syn::PathArguments::AngleBracketed(args) = &segment.arguments {
+                    if let Some(syn::GenericArgument::Type(inner)) = args.args.first() {
+                        return inner;
+                    }
+                }
+            }
+        }
+    }
+    ty
+}
+
+///find flag in attribute #[rbatis(flag)]
+fn has_rbatis_flag(attrs: &Vec<syn::Attribute>, flag: &str) -> bool {
+    for attr in attrs {
+        if !attr.path.is_ident("rbatis") {
+            continue;
+        }
+        match attr.parse_meta() {
+            Ok(syn::Meta::List(list)) => {
+                for nested in list.nested.iter() {
+                    match nested {
+                        syn::NestedMeta::Meta(syn::Meta::Path(path)) => {
+                            if path.is_ident(flag) {
+                                return true;
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    false
+}
+
+///find value in attribute #[rbatis(name = "value")]
+fn find_rbatis_value(attrs: &Vec<syn::Attribute>, name: &str) -> Option<String> {
+    for attr in attrs {
+        if !attr.path.is_ident("rbatis") {
+            continue;
+        }
+        match attr.parse_meta() {
+            Ok(syn::Meta::List(list)) => {
+                for nested in list.nested.iter() {
+                    match nested {
+                        syn::NestedMeta::Meta(syn::Meta::NameValue(name_value)) => {
+                            if name_value.path.is_ident(name) {
+                                if let syn::Lit::Str(lit) = &name_value.lit {
+                                    return Some(lit.value());
+                                }
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+///the table column of field,#[rbatis(column = "...")] or the field name
+fn field_column(field: &syn::Field) -> String {
+    find_rbatis_value(&field.attrs, "column").unwrap_or(field.ident.to_token_stream().to_string())
 }
 
 
 pub(crate) fn impl_macro(ast: &syn::DeriveInput) -> TokenStream {
     println!("............proc_macro_struct sql start............");
     let name = &ast.ident;
-    let id_type = find_id_type_ident(&ast.data);
+    let id_fields = find_id_fields(&ast.data);
+    let id_type = find_id_type(&id_fields);
+    // gen id_columns(),only for the fields marked by #[rbatis(id)]
+    let mut id_columns_fn = quote! {};
+    if id_fields.iter().any(|field| has_rbatis_flag(&field.attrs, "id") || find_rbatis_value(&field.attrs, "column").is_some()) {
+        let id_columns: Vec<String> = id_fields.iter().map(|field| field_column(field)).collect();
+        id_columns_fn = quote! {
+            fn id_columns() -> Vec<String> {
+                 vec![#(#id_columns.to_string()),*]
+            }
+        };
+    }
+    // gen table_name(),#[rbatis(table = "...")] or snake name of struct
+    let table_name_fn = match find_rbatis_value(&ast.attrs, "table") {
+        Some(table) => quote! {
+            fn table_name() -> String {
+                 #table.to_string()
+            }
+        },
+        None => quote! {
+            fn table_name() -> String {
+                 let mut name = stringify!(#name).to_string();
+                 let names: Vec<&str> = name.split("::").collect();
+                 name = names.get(names.len() - 1).unwrap().to_string();
+                 return rbatis::utils::string_util::to_snake_name(&name);
+            }
+        }
+    };
+    // gen version_column(),logic_delete_column(),skip_fields(),column_renames() by field attributes
+    let mut version_column_fn = quote! {};
+    let mut logic_delete_column_fn = quote! {};
+    let mut skip_fields = vec![];
+    let mut column_renames = vec![];
+    // gen column const for every field in <Name>Columns,for example: BizActivityColumns::status
+    let mut column_consts = vec![];
     /// gen fields token
     let mut fields = quote! { String::new() };
     match &ast.data {
@@ -49,6 +173,34 @@ pub(crate) fn impl_macro(ast: &syn::DeriveInput) -> TokenStream {
             let mut index = 0;
             for field in &s.fields {
                 let field_name = &field.ident.to_token_stream().to_string();
+                if has_rbatis_flag(&field.attrs, "skip") {
+                    skip_fields.push(field_name.to_string());
+                    continue;
+                }
+                let column = field_column(field);
+                let field_ident = &field.ident;
+                column_consts.push(quote! {
+                    pub const #field_ident: rbatis::wrapper::Column = rbatis::wrapper::Column::new(#column);
+                });
+                if has_rbatis_flag(&field.attrs, "version") {
+                    version_column_fn = quote! {
+                        fn version_column() -> Option<String> {
+                             Some(#column.to_string())
+                        }
+                    };
+                }
+                if has_rbatis_flag(&field.attrs, "logic_delete") {
+                    logic_delete_column_fn = quote! {
+                        fn logic_delete_column() -> Option<String> {
+                             Some(#column.to_string())
+                        }
+                    };
+                }
+                let mut field_name = field_name.to_string();
+                if !column.eq(&field_name) {
+                    column_renames.push((field_name.to_string(), column.to_string()));
+                    field_name = format!("{} AS {}", column, field_name);
+                }
                 if index == 0 {
                     fields = quote! {
                        #fields+#field_name
@@ -63,21 +215,54 @@ pub(crate) fn impl_macro(ast: &syn::DeriveInput) -> TokenStream {
         }
         _ => {}
     }
+    let mut skip_fields_fn = quote! {};
+    if !skip_fields.is_empty() {
+        skip_fields_fn = quote! {
+            fn skip_fields() -> Vec<String> {
+                 vec![#(#skip_fields.to_string()),*]
+            }
+        };
+    }
+    let mut column_renames_fn = quote! {};
+    if !column_renames.is_empty() {
+        let rename_fields: Vec<&String> = column_renames.iter().map(|(field, _)| field).collect();
+        let rename_columns: Vec<&String> = column_renames.iter().map(|(_, column)| column).collect();
+        column_renames_fn = quote! {
+            fn column_renames() -> Vec<(String, String)> {
+                 vec![#((#rename_fields.to_string(), #rename_columns.to_string())),*]
+            }
+        };
+    }
 
+    let vis = &ast.vis;
+    let columns_name = Ident::new(&format!("{}Columns", name), Span::call_site());
     let gen = quote! {
+        /// the columns of table
+        #vis struct #columns_name;
+
         impl CRUDEnable for #name {
             type IdType = #id_type;
 
-            fn table_name() -> String {
-                 let mut name = stringify!(#name).to_string();
-                 let names: Vec<&str> = name.split("::").collect();
-                 name = names.get(names.len() - 1).unwrap().to_string();
-                 return rbatis::utils::string_util::to_snake_name(&name);
-            }
+            #table_name_fn
 
             fn table_fields() -> String{
                  #fields
             }
+
+            #id_columns_fn
+
+            #version_column_fn
+
+            #logic_delete_column_fn
+
+            #skip_fields_fn
+
+            #column_renames_fn
+        }
+
+        #[allow(non_upper_case_globals)]
+        impl #columns_name {
+            #(#column_consts)*
         }
     };
     println!("............gen rust code:\n {}", format!("{}", gen));
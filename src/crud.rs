@@ -1,6 +1,8 @@
+use std::collections::HashMap;
+
 use async_trait::async_trait;
+use futures_core::stream::BoxStream;
 use serde::de::DeserializeOwned;
-use serde::export::fmt::Display;
 use serde::Serialize;
 use serde_json::{Map, Value};
 
@@ -9,10 +11,12 @@ use rbatis_core::db::DriverType;
 use rbatis_core::Error;
 use rbatis_core::Result;
 
-use crate::plugin::logic_delete::LogicAction;
+use crate::plugin::fill::{FieldFill, FillAction};
+use crate::plugin::logic_delete::{LogicAction, LogicDelete, RbatisLogicDeletePlugin};
 use crate::plugin::page::{IPageRequest, Page};
 use crate::rbatis::Rbatis;
 use crate::sql::date::DateFormat;
+use crate::sql::{BatchLimit, PageLimit, UpsertSql};
 use crate::utils::string_util::to_snake_name;
 use crate::wrapper::Wrapper;
 
@@ -21,8 +25,9 @@ pub trait CRUDEnable: Send + Sync + Serialize + DeserializeOwned {
     /// your table id type,for example:
     /// IdType = String
     /// IdType = i32
+    /// IdType = (i32, String)  (composite id,see id_columns())
     ///
-    type IdType: Send + Sync + DeserializeOwned + Serialize + Display;
+    type IdType: Send + Sync + DeserializeOwned + Serialize;
     /// get table name,default is type name for snake name
     ///
     /// for Example:  struct  BizActivity{} =>  "biz_activity"
@@ -63,17 +68,84 @@ pub trait CRUDEnable: Send + Sync + Serialize + DeserializeOwned {
             return " * ".to_string();
         }
         let m = v.as_object().unwrap();
+        let skip_fields = Self::skip_fields();
+        let column_renames = Self::column_renames();
         let mut fields = String::new();
         for (k, _) in m {
-            fields.push_str(k);
+            if skip_fields.contains(k) {
+                continue;
+            }
+            match column_renames.iter().find(|(field, _)| field.eq(k)) {
+                Some((field, column)) => {
+                    fields.push_str(format!("{} AS {}", column, field).as_str());
+                }
+                None => {
+                    fields.push_str(k);
+                }
+            }
             fields.push_str(",");
         }
         fields.pop();
         return format!(" {} ", fields);
     }
 
-    /// make an Map<table_field,value>
-    fn make_field_value_map<C>(db_type: &DriverType, arg: &C) -> Result<serde_json::Map<String, Value>>
+    /// table primary key column(s),default is ["id"]
+    ///
+    /// for Example(composite id), the IdType must serialize to an array with the same order:
+    ///  impl CRUDEnable for BizOrder{
+    ///   type IdType = (i64, String);
+    ///   fn id_columns() -> Vec<String>{
+    ///     vec!["tenant_id".to_string(), "order_no".to_string()]
+    ///   }
+    /// }
+    ///
+    fn id_columns() -> Vec<String> {
+        vec!["id".to_string()]
+    }
+
+    /// optimistic lock version column,default is None(disable)
+    ///
+    /// for Example:
+    ///  impl CRUDEnable for BizActivity{
+    ///   fn version_column() -> Option<String>{
+    ///     Some("version".to_string())
+    ///   }
+    /// }
+    ///
+    /// update_by_id/update_by_wrapper will make sql: UPDATE biz_activity SET version = version + 1 ... WHERE ... AND version = ?
+    /// and return Error::StaleUpdate if no row is updated
+    fn version_column() -> Option<String> {
+        None
+    }
+
+    /// logic delete column of this table,default is None(use Rbatis.logic_plugin column)
+    /// if set, the table will use this column to logic delete,and the deleted/un_deleted value from Rbatis.logic_plugin(default 1/0)
+    fn logic_delete_column() -> Option<String> {
+        None
+    }
+
+    /// the struct fields which are not table column,default is empty
+    /// the skip fields will not insert/update/select(the field must can be default, for example Option<T>)
+    fn skip_fields() -> Vec<String> {
+        vec![]
+    }
+
+    /// the struct field name is different from table column,(field,column),default is empty
+    ///
+    /// for Example:
+    ///  impl CRUDEnable for BizActivity{
+    ///   fn column_renames() -> Vec<(String, String)>{
+    ///     vec![("name".to_string(), "activity_name".to_string())]
+    ///   }
+    /// }
+    ///
+    /// select will make sql: SELECT activity_name AS name ... , insert/update use the column activity_name
+    fn column_renames() -> Vec<(String, String)> {
+        vec![]
+    }
+
+    /// make an Map<table_field,value>,and fill the columns by the fill plugin(Rbatis.fill_plugin) for insert/update
+    fn make_field_value_map<C>(db_type: &DriverType, fill_plugin: &Option<Box<dyn FieldFill>>, action: FillAction, arg: &C) -> Result<serde_json::Map<String, Value>>
         where C: CRUDEnable {
         let json = serde_json::to_value(arg).unwrap_or(serde_json::Value::Null);
         if json.eq(&serde_json::Value::Null) {
@@ -82,7 +154,20 @@ pub trait CRUDEnable: Send + Sync + Serialize + DeserializeOwned {
         if !json.is_object() {
             return Err(Error::from("[rbaits] to_value_map() fail,data is not an object!"));
         }
-        return Ok(json.as_object().unwrap().to_owned());
+        let mut map = json.as_object().unwrap().to_owned();
+        for field in Self::skip_fields() {
+            map.remove(&field);
+        }
+        for (field, column) in Self::column_renames() {
+            let v = map.remove(&field);
+            if v.is_some() {
+                map.insert(column, v.unwrap());
+            }
+        }
+        if fill_plugin.is_some() {
+            fill_plugin.as_ref().unwrap().do_fill(db_type, &Self::table_name(), &make_table_columns(&Self::table_fields()), action, &mut map)?;
+        }
+        return Ok(map);
     }
 
     ///make fields
@@ -147,9 +232,29 @@ impl<T> CRUDEnable for Option<T> where T: CRUDEnable {
         T::table_fields()
     }
 
+    fn id_columns() -> Vec<String> {
+        T::id_columns()
+    }
+
+    fn version_column() -> Option<String> {
+        T::version_column()
+    }
+
+    fn logic_delete_column() -> Option<String> {
+        T::logic_delete_column()
+    }
+
+    fn skip_fields() -> Vec<String> {
+        T::skip_fields()
+    }
+
+    fn column_renames() -> Vec<(String, String)> {
+        T::column_renames()
+    }
+
 
-    fn make_field_value_map<C>(db_type: &DriverType, arg: &C) -> Result<Map<String, Value>> where C: CRUDEnable {
-        T::make_field_value_map(db_type, arg)
+    fn make_field_value_map<C>(db_type: &DriverType, fill_plugin: &Option<Box<dyn FieldFill>>, action: FillAction, arg: &C) -> Result<Map<String, Value>> where C: CRUDEnable {
+        T::make_field_value_map(db_type, fill_plugin, action, arg)
     }
 
     fn make_fields(map: &Map<String, Value>) -> Result<String> {
@@ -179,7 +284,7 @@ impl<T> CRUDEnable for Option<T> where T: CRUDEnable {
 ///         println!("{:?}", ids);
 ///
 pub trait Id {
-    type IdType: Send + Sync + DeserializeOwned + Serialize + Display;
+    type IdType: Send + Sync + DeserializeOwned + Serialize;
     fn get_id(&self) -> Option<Self::IdType>;
 }
 
@@ -202,11 +307,72 @@ impl<C> Ids<C> for Vec<C> where C: Id {
     }
 }
 
+/// one-to-many relation,the parent(Self) has many children(C),must use Id trait together
+///
+/// for example:
+///     impl HasMany<BizActivityItem> for BizActivity {
+///         fn foreign_column() -> String {
+///             "activity_id".to_string()
+///         }
+///         fn foreign_key(child: &BizActivityItem) -> Option<Self::IdType> {
+///             child.activity_id.clone()
+///         }
+///         fn set_children(&mut self, children: Vec<BizActivityItem>) {
+///             self.items = Some(children);
+///         }
+///     }
+/// rb.load_has_many::<BizActivity, BizActivityItem>("", &mut activities).await;
+/// [rbatis] Query ==> SELECT ... FROM biz_activity_item WHERE activity_id IN ( ? , ? )
+///
+pub trait HasMany<C>: Id + Send + Sync where C: CRUDEnable {
+    /// the child table column which reference the parent id
+    fn foreign_column() -> String;
+    /// the parent id of child
+    fn foreign_key(child: &C) -> Option<Self::IdType>;
+    /// set the children of parent
+    fn set_children(&mut self, children: Vec<C>);
+}
+
+/// many-to-one relation,the child(Self) belongs to parent(P),the parent must use Id trait together
+///
+/// for example:
+///     impl BelongsTo<BizActivity> for BizActivityItem {
+///         fn parent_id(&self) -> Option<String> {
+///             self.activity_id.clone()
+///         }
+///         fn set_parent(&mut self, parent: Option<BizActivity>) {
+///             self.activity = parent;
+///         }
+///     }
+/// rb.load_belongs_to::<BizActivityItem, BizActivity>("", &mut items).await;
+/// [rbatis] Query ==> SELECT ... FROM biz_activity WHERE id IN ( ? , ? )
+///
+pub trait BelongsTo<P>: Send + Sync where P: CRUDEnable {
+    /// the parent id of child
+    fn parent_id(&self) -> Option<P::IdType>;
+    /// set the parent of child
+    fn set_parent(&mut self, parent: Option<P>);
+}
+
+/// the way of update_batch_by_id_mode()
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum UpdateBatchMode {
+    /// one UPDATE sql per entity
+    Loop,
+    /// one UPDATE ... SET column = CASE WHEN id = ? THEN ? ... END WHERE id IN (...) sql per batch
+    CaseWhen,
+}
+
 #[async_trait]
 pub trait CRUD {
     /// tx_id: Transaction id,default ""
     async fn save<T>(&self, tx_id: &str, entity: &T) -> Result<u64> where T: CRUDEnable;
     async fn save_batch<T>(&self, tx_id: &str, entity: &[T]) -> Result<u64> where T: CRUDEnable;
+    async fn save_batch_in_tx<T>(&self, entity: &[T]) -> Result<u64> where T: CRUDEnable;
+    async fn save_returning_id<T>(&self, tx_id: &str, entity: &T) -> Result<T::IdType> where T: CRUDEnable;
+    async fn save_batch_returning_id<T>(&self, tx_id: &str, entity: &[T]) -> Result<Vec<T::IdType>> where T: CRUDEnable;
+    async fn save_or_update<T>(&self, tx_id: &str, entity: &T, conflict_columns: &[&str], update_columns: &[&str]) -> Result<u64> where T: CRUDEnable;
+    async fn save_or_update_batch<T>(&self, tx_id: &str, entity: &[T], conflict_columns: &[&str], update_columns: &[&str]) -> Result<u64> where T: CRUDEnable;
 
 
     async fn remove_by_wrapper<T>(&self, tx_id: &str, w: &Wrapper) -> Result<u64> where T: CRUDEnable;
@@ -216,6 +382,7 @@ pub trait CRUD {
     async fn update_by_wrapper<T>(&self, tx_id: &str, arg: &T, w: &Wrapper, update_null_value: bool) -> Result<u64> where T: CRUDEnable;
     async fn update_by_id<T>(&self, tx_id: &str, arg: &T) -> Result<u64> where T: CRUDEnable;
     async fn update_batch_by_id<T>(&self, tx_id: &str, ids: &[T]) -> Result<u64> where T: CRUDEnable;
+    async fn update_batch_by_id_mode<T>(&self, tx_id: &str, ids: &[T], mode: UpdateBatchMode) -> Result<u64> where T: CRUDEnable;
 
     async fn fetch_by_wrapper<T>(&self, tx_id: &str, w: &Wrapper) -> Result<T> where T: CRUDEnable;
     async fn fetch_by_id<T>(&self, tx_id: &str, id: &T::IdType) -> Result<T> where T: CRUDEnable;
@@ -225,6 +392,23 @@ pub trait CRUD {
     async fn list<T>(&self, tx_id: &str) -> Result<Vec<T>> where T: CRUDEnable;
     async fn list_by_wrapper<T>(&self, tx_id: &str, w: &Wrapper) -> Result<Vec<T>> where T: CRUDEnable;
     async fn list_by_ids<T>(&self, tx_id: &str, ids: &[T::IdType]) -> Result<Vec<T>> where T: CRUDEnable;
+    async fn stream_by_wrapper<'a, T>(&'a self, tx_id: &str, w: &Wrapper) -> Result<BoxStream<'a, Result<T>>> where T: CRUDEnable + 'a;
+
+    /// select the columns of table T,and decode into R
+    async fn fetch_by_wrapper_columns<T, R>(&self, tx_id: &str, w: &Wrapper, columns: &[&str]) -> Result<R> where T: CRUDEnable, R: DeserializeOwned;
+    async fn list_by_wrapper_columns<T, R>(&self, tx_id: &str, w: &Wrapper, columns: &[&str]) -> Result<Vec<R>> where T: CRUDEnable, R: DeserializeOwned;
+
+    /// aggregate of table T
+    async fn count_by_wrapper<T>(&self, tx_id: &str, w: &Wrapper) -> Result<u64> where T: CRUDEnable;
+    async fn exists_by_wrapper<T>(&self, tx_id: &str, w: &Wrapper) -> Result<bool> where T: CRUDEnable;
+    async fn sum_by_wrapper<T, R>(&self, tx_id: &str, w: &Wrapper, column: &str) -> Result<R> where T: CRUDEnable, R: DeserializeOwned;
+    async fn max_by_wrapper<T, R>(&self, tx_id: &str, w: &Wrapper, column: &str) -> Result<R> where T: CRUDEnable, R: DeserializeOwned;
+    async fn min_by_wrapper<T, R>(&self, tx_id: &str, w: &Wrapper, column: &str) -> Result<R> where T: CRUDEnable, R: DeserializeOwned;
+    async fn avg_by_wrapper<T, R>(&self, tx_id: &str, w: &Wrapper, column: &str) -> Result<R> where T: CRUDEnable, R: DeserializeOwned;
+
+    /// relation batch load,one IN (...) query per relation
+    async fn load_has_many<P, C>(&self, tx_id: &str, parents: &mut [P]) -> Result<()> where P: HasMany<C>, C: CRUDEnable + Clone;
+    async fn load_belongs_to<C, P>(&self, tx_id: &str, children: &mut [C]) -> Result<()> where C: BelongsTo<P>, P: CRUDEnable + Id + Clone;
 }
 
 #[async_trait]
@@ -232,14 +416,13 @@ impl CRUD for Rbatis {
     /// save one entity to database
     async fn save<T>(&self, tx_id: &str, entity: &T) -> Result<u64>
         where T: CRUDEnable {
-        let map = T::make_field_value_map(&self.driver_type()?, entity)?;
-        let mut index = 0;
-        let (values, args) = T::make_sql_arg(&mut index, &self.driver_type()?, &map)?;
-        let sql = format!("INSERT INTO {} ({}) VALUES ({})", T::table_name(), T::make_fields(&map)?, values);
+        let (sql, args, _) = make_insert_sql(&self.driver_type()?, &self.fill_plugin, std::slice::from_ref(entity), false)?;
         return self.exec_prepare(tx_id, sql.as_str(), &args).await;
     }
 
-    /// save batch makes many value into  only one sql. make sure your data not  to long!
+    /// save batch makes many value into sql,
+    /// the data will split into many sql if the bind params count over DriverType.max_bind_params()
+    /// return the total rows_affected
     ///
     /// for Example:
     /// rb.save_batch(&vec![activity]);
@@ -250,49 +433,118 @@ impl CRUD for Rbatis {
         if args.is_empty() {
             return Ok(0);
         }
-        let mut value_arr = String::new();
-        let mut arg_arr = vec![];
-        let mut fields = "".to_string();
-        let mut field_index = 0;
-        for x in args {
-            let map = T::make_field_value_map(&self.driver_type()?, x)?;
-            if fields.is_empty() {
-                fields = T::make_fields(&map)?;
+        let driver_type = self.driver_type()?;
+        let chunk_size = make_batch_chunk_size(&driver_type, &self.fill_plugin, &args[0])?;
+        let mut rows_affected = 0;
+        for chunk in args.chunks(chunk_size) {
+            let (sql, args, _) = make_insert_sql(&driver_type, &self.fill_plugin, chunk, false)?;
+            rows_affected += self.exec_prepare(tx_id, sql.as_str(), &args).await?;
+        }
+        return Ok(rows_affected);
+    }
+
+    /// same as save_batch(),but all the chunks will save in one new transaction(rollback if any chunk fail,or panic/cancel)
+    async fn save_batch_in_tx<T>(&self, args: &[T]) -> Result<u64> where T: CRUDEnable {
+        if args.is_empty() {
+            return Ok(0);
+        }
+        self.transaction(|tx_id| async move {
+            self.save_batch(tx_id.as_str(), args).await
+        }).await
+    }
+
+    /// save one entity and return the generated id,the null id column will not insert
+    /// mysql: LAST_INSERT_ID, postgres: RETURNING id, sqlite: last_insert_rowid()
+    /// if the entity id is not null,mysql/sqlite return it. if it is null,the IdType must be an integer
+    ///
+    /// for Example:
+    /// let id: i64 = rb.save_returning_id("", &activity).await?;
+    /// [rbatis] Exec ==> INSERT INTO biz_activity (name,version) VALUES ( ? , ? )
+    ///
+    async fn save_returning_id<T>(&self, tx_id: &str, entity: &T) -> Result<T::IdType> where T: CRUDEnable {
+        let driver_type = self.driver_type()?;
+        let id_column = make_single_id_column::<T>()?;
+        let (mut sql, args, _) = make_insert_sql(&driver_type, &self.fill_plugin, std::slice::from_ref(entity), true)?;
+        match driver_type {
+            DriverType::Postgres => {
+                sql.push_str(format!(" RETURNING {}", id_column).as_str());
+                let rows: Vec<Value> = self.fetch_prepare(tx_id, sql.as_str(), &args).await?;
+                let mut ids = decode_ids::<T>(rows)?;
+                if ids.len() != 1 {
+                    return Err(Error::from(format!("[rbatis] save_returning_id() returning {} ids,but expect 1!", ids.len())));
+                }
+                return Ok(ids.pop().unwrap());
             }
-            let (values, args) = T::make_sql_arg(&mut field_index, &self.driver_type()?, &map)?;
-            value_arr = value_arr + format!("({}),", values).as_str();
-            for x in args {
-                arg_arr.push(x);
+            _ => {
+                //the entity have id,return it
+                let id = T::make_field_value_map(&driver_type, &self.fill_plugin, FillAction::Insert, entity)?.remove(&id_column).unwrap_or(Value::Null);
+                if !id.is_null() {
+                    let id = serde_json::from_value(id).map_err(|e| Error::from(format!("[rbatis] decode id fail: {}", e)))?;
+                    self.exec_prepare(tx_id, sql.as_str(), &args).await?;
+                    return Ok(id);
+                }
+                //check the IdType before insert
+                decode_last_id::<T>(0)?;
+                let (_, last_id) = self.exec_prepare_last_id(tx_id, sql.as_str(), &args).await?;
+                return decode_last_id::<T>(last_id);
             }
         }
-        value_arr.pop();//pop ','
-        let sql = format!("INSERT INTO {} ({}) VALUES {}", T::table_name(), fields, value_arr);
-        return self.exec_prepare(tx_id, sql.as_str(), &arg_arr).await;
     }
 
-    async fn remove_by_wrapper<T>(&self, tx_id: &str, w: &Wrapper) -> Result<u64> where T: CRUDEnable {
-        let w = match w.checked {
-            false => w.clone().check()?,
-            _ => w.clone()
-        };
-        let where_sql = w.sql.as_str();
-        let mut sql = String::new();
-        if self.logic_plugin.is_some() {
-            sql = self.logic_plugin.as_ref().unwrap().create_remove_sql(&self.driver_type()?, T::table_name().as_str(), &T::table_fields(), make_where_sql(where_sql).as_str())?;
-        } else {
-            sql = format!("DELETE FROM {} {}", T::table_name(), make_where_sql(where_sql));
+    /// save batch and return the generated ids,only support postgres(RETURNING)
+    async fn save_batch_returning_id<T>(&self, tx_id: &str, args: &[T]) -> Result<Vec<T::IdType>> where T: CRUDEnable {
+        if args.is_empty() {
+            return Ok(vec![]);
+        }
+        let driver_type = self.driver_type()?;
+        if !driver_type.eq(&DriverType::Postgres) {
+            return Err(Error::from(format!("[rbatis] save_batch_returning_id() not support DriverType:{:?},please use save_returning_id()!", driver_type)));
+        }
+        let id_column = make_single_id_column::<T>()?;
+        let (mut sql, args, _) = make_insert_sql(&driver_type, &self.fill_plugin, args, true)?;
+        sql.push_str(format!(" RETURNING {}", id_column).as_str());
+        let rows: Vec<Value> = self.fetch_prepare(tx_id, sql.as_str(), &args).await?;
+        return decode_ids::<T>(rows);
+    }
+
+    /// save entity,or update it if the conflict columns is exist.
+    /// conflict_columns: default(empty) is T::id_columns(),mysql always use the table's primary/unique key(must be empty,else return error)
+    /// update_columns: the columns to update,default(empty) is all columns without conflict columns
+    ///
+    /// for Example:
+    /// rb.save_or_update("", &activity, &[], &["name"]).await;
+    /// [rbatis] Exec ==> INSERT INTO biz_activity (id,name) VALUES (?,?) ON DUPLICATE KEY UPDATE name = VALUES(name)
+    /// [rbatis] Exec ==> INSERT INTO biz_activity (id,name) VALUES ($1,$2) ON CONFLICT (id) DO UPDATE SET name = excluded.name
+    ///
+    async fn save_or_update<T>(&self, tx_id: &str, entity: &T, conflict_columns: &[&str], update_columns: &[&str]) -> Result<u64> where T: CRUDEnable {
+        return self.save_or_update_batch(tx_id, std::slice::from_ref(entity), conflict_columns, update_columns).await;
+    }
+
+    /// save_or_update batch makes many value into only one sql
+    async fn save_or_update_batch<T>(&self, tx_id: &str, args: &[T], conflict_columns: &[&str], update_columns: &[&str]) -> Result<u64> where T: CRUDEnable {
+        if args.is_empty() {
+            return Ok(0);
         }
+        let driver_type = self.driver_type()?;
+        let (mut sql, args, columns) = make_insert_sql(&driver_type, &self.fill_plugin, args, false)?;
+        sql.push_str(make_upsert_sql::<T>(&driver_type, &columns, conflict_columns, update_columns)?.as_str());
+        return self.exec_prepare(tx_id, sql.as_str(), &args).await;
+    }
+
+    async fn remove_by_wrapper<T>(&self, tx_id: &str, w: &Wrapper) -> Result<u64> where T: CRUDEnable {
+        let w = w.render(&self.driver_type()?)?;
+        let sql = make_remove_sql::<T>(&self.driver_type()?, &self.logic_plugin, w.sql.as_str())?;
         return self.exec_prepare(tx_id, sql.as_str(), &w.args).await;
     }
 
+    /// remove by id,the id is always a bind arg
+    /// for Example :
+    /// rb.remove_by_id::<BizActivity>("", &"1".to_string()).await;
+    /// [rbatis] Exec ==> DELETE FROM biz_activity WHERE id = ?
+    ///
     async fn remove_by_id<T>(&self, tx_id: &str, id: &T::IdType) -> Result<u64> where T: CRUDEnable {
-        let mut sql = String::new();
-        if self.logic_plugin.is_some() {
-            sql = self.logic_plugin.as_ref().unwrap().create_remove_sql(&self.driver_type()?, T::table_name().as_str(), &T::table_fields(), format!(" WHERE id = {}", id).as_str())?;
-        } else {
-            sql = format!("DELETE FROM {} WHERE id = {}", T::table_name(), id);
-        }
-        return self.exec_prepare(tx_id, sql.as_str(), &vec![]).await;
+        let w = make_id_wrapper::<T, _>(&self.driver_type()?, id)?;
+        return self.remove_by_wrapper::<T>(tx_id, &w).await;
     }
 
     ///remove batch id
@@ -304,80 +556,81 @@ impl CRUD for Rbatis {
         if ids.is_empty() {
             return Ok(0);
         }
-        let w = Wrapper::new(&self.driver_type()?).and().in_array("id", &ids).check()?;
+        let w = make_ids_wrapper::<T, _>(&self.driver_type()?, ids)?;
         return self.remove_by_wrapper::<T>(tx_id, &w).await;
     }
 
     /// update arg by wrapper
+    /// if T::version_column() is not None and the arg's version is not null,
+    /// the version column will be increased and checked by optimistic lock
     async fn update_by_wrapper<T>(&self, tx_id: &str, arg: &T, w: &Wrapper, update_null_value: bool) -> Result<u64> where T: CRUDEnable {
-        let w = match w.checked {
-            false => w.clone().check()?,
-            _ => w.clone()
-        };
-        let mut args = vec![];
-        let map = T::make_field_value_map(&self.driver_type()?, arg)?;
         let driver_type = &self.driver_type()?;
-        let mut sets = String::new();
-        for (k, v) in map {
-            //filter id
-            if k.eq("id") {
-                continue;
-            }
-            //filter null
-            if !update_null_value && v.is_null() {
-                continue;
-            }
-            sets.push_str(format!(" {} = {},", k, driver_type.stmt_convert(args.len())).as_str());
-            args.push(v);
-        }
-        sets.pop();
-        let mut wrapper = Wrapper::new(&self.driver_type()?);
-        wrapper.sql = format!("UPDATE {} SET {}", T::table_name(), sets);
-        wrapper.args = args;
-        if !w.sql.is_empty() {
-            wrapper.sql.push_str(" WHERE ");
-            wrapper = wrapper.push_wrapper(&w).check()?;
+        let w = w.render(driver_type)?;
+        let (wrapper, version_value) = make_update_sql(driver_type, &self.fill_plugin, arg, &w, update_null_value)?;
+        let rows_affected = self.exec_prepare(tx_id, wrapper.sql.as_str(), &wrapper.args).await?;
+        if version_value.is_some() && rows_affected == 0 {
+            return Err(Error::StaleUpdate(format!("[rbatis] update {} fail,the {} = {} is stale!", T::table_name(), T::version_column().unwrap_or_default(), version_value.unwrap())));
         }
-        return self.exec_prepare(tx_id, wrapper.sql.as_str(), &wrapper.args).await;
+        return Ok(rows_affected);
     }
 
     async fn update_by_id<T>(&self, tx_id: &str, arg: &T) -> Result<u64> where T: CRUDEnable {
-        let args = T::make_field_value_map(&self.driver_type()?, arg)?;
-        let id_field = args.get("id");
-        if id_field.is_none() {
-            return Err(Error::from("[rbaits] arg not have \"id\" field! "));
-        }
-        self.update_by_wrapper(tx_id, arg, Wrapper::new(&self.driver_type()?).eq("id", id_field.unwrap()), false).await
+        let args = T::make_field_value_map(&self.driver_type()?, &None, FillAction::Update, arg)?;
+        let id = make_id_value::<T>(&args)?;
+        let w = make_id_wrapper::<T, _>(&self.driver_type()?, &id)?;
+        self.update_by_wrapper(tx_id, arg, &w, false).await
     }
 
     async fn update_batch_by_id<T>(&self, tx_id: &str, args: &[T]) -> Result<u64> where T: CRUDEnable {
+        self.update_batch_by_id_mode(tx_id, args, UpdateBatchMode::Loop).await
+    }
+
+    /// update batch by id,the mode can be UpdateBatchMode::Loop or UpdateBatchMode::CaseWhen
+    /// CaseWhen will make one sql per batch(the null value will not update),
+    /// but the table have version_column() will still use Loop(check every row's version)
+    ///
+    /// for Example:
+    /// rb.update_batch_by_id_mode("", &activities, UpdateBatchMode::CaseWhen).await;
+    /// [rbatis] Exec ==> UPDATE biz_activity SET name = CASE WHEN id = ? THEN ? WHEN id = ? THEN ? ELSE name END WHERE id IN (?,?)
+    ///
+    async fn update_batch_by_id_mode<T>(&self, tx_id: &str, args: &[T], mode: UpdateBatchMode) -> Result<u64> where T: CRUDEnable {
+        if args.is_empty() {
+            return Ok(0);
+        }
+        if mode == UpdateBatchMode::Loop || T::version_column().is_some() {
+            let mut updates = 0;
+            for x in args {
+                updates += self.update_by_id(tx_id, x).await?
+            }
+            return Ok(updates);
+        }
+        let driver_type = self.driver_type()?;
+        let chunk_size = make_update_batch_chunk_size(&driver_type, &self.fill_plugin, &args[0])?;
         let mut updates = 0;
-        for x in args {
-            updates += self.update_by_id(tx_id, x).await?
+        for chunk in args.chunks(chunk_size) {
+            let (sql, args) = make_update_batch_sql(&driver_type, &self.fill_plugin, chunk)?;
+            if sql.is_empty() {
+                continue;
+            }
+            updates += self.exec_prepare(tx_id, sql.as_str(), &args).await?;
         }
         Ok(updates)
     }
 
     async fn fetch_by_wrapper<T>(&self, tx_id: &str, w: &Wrapper) -> Result<T> where T: CRUDEnable {
-        let w = match w.checked {
-            false => w.clone().check()?,
-            _ => w.clone()
-        };
-        let sql = make_select_sql::<T>(&self, &w)?;
+        let w = w.render(&self.driver_type()?)?;
+        let sql = make_wrapper_select_sql::<T>(&self.driver_type()?, &self.logic_plugin, None, &w, true)?;
         return self.fetch_prepare(tx_id, sql.as_str(), &w.args).await;
     }
 
     async fn fetch_by_id<T>(&self, tx_id: &str, id: &T::IdType) -> Result<T> where T: CRUDEnable {
-        let w = Wrapper::new(&self.driver_type()?).eq("id", id).check()?;
+        let w = make_id_wrapper::<T, _>(&self.driver_type()?, id)?;
         return self.fetch_by_wrapper(tx_id, &w).await;
     }
 
     async fn list_by_wrapper<T>(&self, tx_id: &str, w: &Wrapper) -> Result<Vec<T>> where T: CRUDEnable {
-        let w = match w.checked {
-            false => w.clone().check()?,
-            _ => w.clone()
-        };
-        let sql = make_select_sql::<T>(&self, &w)?;
+        let w = w.render(&self.driver_type()?)?;
+        let sql = make_wrapper_select_sql::<T>(&self.driver_type()?, &self.logic_plugin, None, &w, true)?;
         return self.fetch_prepare(tx_id, sql.as_str(), &w.args).await;
     }
 
@@ -386,18 +639,169 @@ impl CRUD for Rbatis {
     }
 
     async fn list_by_ids<T>(&self, tx_id: &str, ids: &[T::IdType]) -> Result<Vec<T>> where T: CRUDEnable {
-        let w = Wrapper::new(&self.driver_type()?).in_array("id", ids).check()?;
+        if ids.is_empty() {
+            return Ok(vec![]);
+        }
+        let w = make_ids_wrapper::<T, _>(&self.driver_type()?, ids)?;
         return self.list_by_wrapper(tx_id, &w).await;
     }
 
     async fn fetch_page_by_wrapper<T>(&self, tx_id: &str, w: &Wrapper, page: &dyn IPageRequest) -> Result<Page<T>> where T: CRUDEnable {
-        let w = match w.checked {
-            false => w.clone().check()?,
-            _ => w.clone()
-        };
-        let sql = make_select_sql::<T>(&self, &w)?;
+        let w = w.render(&self.driver_type()?)?;
+        //the page plugin create limit sql,so ignore the wrapper limit
+        let sql = make_wrapper_select_sql::<T>(&self.driver_type()?, &self.logic_plugin, None, &w, false)?;
         self.fetch_page(tx_id, sql.as_str(), &w.args, page).await
     }
+
+    /// fetch the records one by one as stream,for export many rows without collect into Vec
+    ///
+    /// for Example:
+    /// use async_std::stream::StreamExt;
+    /// let mut stream = rb.stream_by_wrapper::<BizActivity>("", &w).await?;
+    /// while let Some(item) = stream.next().await {
+    ///     let activity: BizActivity = item?;
+    /// }
+    ///
+    async fn stream_by_wrapper<'a, T>(&'a self, tx_id: &str, w: &Wrapper) -> Result<BoxStream<'a, Result<T>>> where T: CRUDEnable + 'a {
+        let w = w.render(&self.driver_type()?)?;
+        let sql = make_wrapper_select_sql::<T>(&self.driver_type()?, &self.logic_plugin, None, &w, true)?;
+        return self.fetch_prepare_stream(tx_id, sql.as_str(), &w.args).await;
+    }
+
+    /// fetch the columns of table T into R,the logic delete plugin will still work
+    ///
+    /// for Example:
+    /// let name: Option<String> = rb.fetch_by_wrapper_columns::<BizActivity, _>("", &w, &["name"]).await?;
+    /// [rbatis] Query ==> SELECT name FROM biz_activity WHERE delete_flag = 0 AND id = ?
+    ///
+    async fn fetch_by_wrapper_columns<T, R>(&self, tx_id: &str, w: &Wrapper, columns: &[&str]) -> Result<R> where T: CRUDEnable, R: DeserializeOwned {
+        let w = w.render(&self.driver_type()?)?;
+        let sql = make_wrapper_select_sql::<T>(&self.driver_type()?, &self.logic_plugin, Some(&make_columns(columns)?), &w, true)?;
+        return self.fetch_prepare(tx_id, sql.as_str(), &w.args).await;
+    }
+
+    /// list the columns of table T into Vec<R>,R can be a smaller struct(DTO)
+    ///
+    /// for Example:
+    /// let names: Vec<ActivityName> = rb.list_by_wrapper_columns::<BizActivity, _>("", &w, &["id", "name"]).await?;
+    /// [rbatis] Query ==> SELECT id,name FROM biz_activity WHERE delete_flag = 0
+    ///
+    async fn list_by_wrapper_columns<T, R>(&self, tx_id: &str, w: &Wrapper, columns: &[&str]) -> Result<Vec<R>> where T: CRUDEnable, R: DeserializeOwned {
+        let w = w.render(&self.driver_type()?)?;
+        let sql = make_wrapper_select_sql::<T>(&self.driver_type()?, &self.logic_plugin, Some(&make_columns(columns)?), &w, true)?;
+        return self.fetch_prepare(tx_id, sql.as_str(), &w.args).await;
+    }
+
+    /// count of table T,the logic delete plugin will still work
+    /// the count(1) is the joined rows if the wrapper have an one-to-many join(left_join,inner_join)
+    ///
+    /// for Example:
+    /// let count = rb.count_by_wrapper::<BizActivity>("", &w).await?;
+    /// [rbatis] Query ==> SELECT count(1) FROM biz_activity WHERE delete_flag = 0 AND name = ?
+    ///
+    async fn count_by_wrapper<T>(&self, tx_id: &str, w: &Wrapper) -> Result<u64> where T: CRUDEnable {
+        self.fetch_by_wrapper_columns::<T, u64>(tx_id, &make_aggregate_wrapper(w)?, &["count(1)"]).await
+    }
+
+    /// is there any record match the wrapper
+    ///
+    /// for Example:
+    /// let exists = rb.exists_by_wrapper::<BizActivity>("", &w).await?;
+    /// [rbatis] Query ==> SELECT 1 FROM biz_activity WHERE delete_flag = 0 AND name = ? LIMIT 0,1
+    ///
+    async fn exists_by_wrapper<T>(&self, tx_id: &str, w: &Wrapper) -> Result<bool> where T: CRUDEnable {
+        let w = w.render(&self.driver_type()?)?;
+        let driver_type = self.driver_type()?;
+        let mut sql = make_wrapper_select_sql::<T>(&driver_type, &self.logic_plugin, Some("1"), &w, false)?;
+        sql.push_str(driver_type.page_limit_sql(0, 1)?.as_str());
+        let rows: Vec<Value> = self.fetch_prepare(tx_id, sql.as_str(), &w.args).await?;
+        return Ok(!rows.is_empty());
+    }
+
+    /// sum of column,R can be Option<i64>,Option<f64>...(null if no record)
+    ///
+    /// for Example:
+    /// let sum: Option<i64> = rb.sum_by_wrapper::<BizActivity, _>("", &w, "status").await?;
+    /// [rbatis] Query ==> SELECT sum(status) FROM biz_activity WHERE delete_flag = 0
+    ///
+    async fn sum_by_wrapper<T, R>(&self, tx_id: &str, w: &Wrapper, column: &str) -> Result<R> where T: CRUDEnable, R: DeserializeOwned {
+        let w = make_aggregate_wrapper(w)?;
+        let column = make_aggregate_column(&self.driver_type()?, &w, "sum", column)?;
+        self.fetch_by_wrapper_columns::<T, R>(tx_id, &w, &[column.as_str()]).await
+    }
+
+    /// max of column
+    async fn max_by_wrapper<T, R>(&self, tx_id: &str, w: &Wrapper, column: &str) -> Result<R> where T: CRUDEnable, R: DeserializeOwned {
+        let w = make_aggregate_wrapper(w)?;
+        let column = make_aggregate_column(&self.driver_type()?, &w, "max", column)?;
+        self.fetch_by_wrapper_columns::<T, R>(tx_id, &w, &[column.as_str()]).await
+    }
+
+    /// min of column
+    async fn min_by_wrapper<T, R>(&self, tx_id: &str, w: &Wrapper, column: &str) -> Result<R> where T: CRUDEnable, R: DeserializeOwned {
+        let w = make_aggregate_wrapper(w)?;
+        let column = make_aggregate_column(&self.driver_type()?, &w, "min", column)?;
+        self.fetch_by_wrapper_columns::<T, R>(tx_id, &w, &[column.as_str()]).await
+    }
+
+    /// avg of column
+    async fn avg_by_wrapper<T, R>(&self, tx_id: &str, w: &Wrapper, column: &str) -> Result<R> where T: CRUDEnable, R: DeserializeOwned {
+        let w = make_aggregate_wrapper(w)?;
+        let column = make_aggregate_column(&self.driver_type()?, &w, "avg", column)?;
+        self.fetch_by_wrapper_columns::<T, R>(tx_id, &w, &[column.as_str()]).await
+    }
+
+    /// load the children of parents by one query: SELECT ... FROM child WHERE foreign_column IN (parent ids)
+    /// the parent which have no child will set an empty vec
+    async fn load_has_many<P, C>(&self, tx_id: &str, parents: &mut [P]) -> Result<()> where P: HasMany<C>, C: CRUDEnable + Clone {
+        let mut ids = vec![];
+        for parent in parents.iter() {
+            let id = parent.get_id();
+            if id.is_some() {
+                ids.push(id.unwrap());
+            }
+        }
+        let mut children: Vec<C> = vec![];
+        if !ids.is_empty() {
+            let mut w = Wrapper::new(&self.driver_type()?);
+            w.in_array(&P::foreign_column(), &ids);
+            children = self.list_by_wrapper(tx_id, &w.check()?).await?;
+        }
+        let groups = group_by_key(children, |child| P::foreign_key(child));
+        for parent in parents.iter_mut() {
+            let children = match make_relation_key(&parent.get_id()) {
+                Some(key) => groups.get(&key).cloned().unwrap_or(vec![]),
+                None => vec![]
+            };
+            parent.set_children(children);
+        }
+        Ok(())
+    }
+
+    /// load the parent of children by one query: SELECT ... FROM parent WHERE id IN (parent ids)
+    /// the child which parent not found will set None
+    async fn load_belongs_to<C, P>(&self, tx_id: &str, children: &mut [C]) -> Result<()> where C: BelongsTo<P>, P: CRUDEnable + Id + Clone {
+        let mut ids = vec![];
+        let mut keys = vec![];
+        for child in children.iter() {
+            let id = child.parent_id();
+            let key = make_relation_key(&id);
+            if key.is_some() && !keys.contains(&key) {
+                keys.push(key);
+                ids.push(id.unwrap());
+            }
+        }
+        let parents: Vec<P> = self.list_by_ids::<P>(tx_id, &ids).await?;
+        let groups = group_by_key(parents, |parent| parent.get_id());
+        for child in children.iter_mut() {
+            let parent = match make_relation_key(&child.parent_id()) {
+                Some(key) => groups.get(&key).and_then(|x| x.first().cloned()),
+                None => None
+            };
+            child.set_parent(parent);
+        }
+        Ok(())
+    }
 }
 
 fn make_where_sql(arg: &str) -> String {
@@ -406,21 +810,427 @@ fn make_where_sql(arg: &str) -> String {
     format!(" WHERE {} ", where_sql)
 }
 
-fn make_select_sql<T>(rb: &Rbatis, w: &Wrapper) -> Result<String> where T: CRUDEnable {
-    let where_sql = w.sql.clone();
-    let mut sql = String::new();
-    if rb.logic_plugin.is_some() {
-        let logic_ref = rb.logic_plugin.as_ref().unwrap();
-        return logic_ref.create_select_sql(&rb.driver_type()?, &T::table_name(), &T::table_fields(), &where_sql);
+/// the columns of table_fields,for example: "user_id AS id,name" => ["user_id","name"]
+fn make_table_columns(table_fields: &str) -> Vec<String> {
+    let mut columns = vec![];
+    for x in table_fields.split(",") {
+        let column = x.split(" AS ").next().unwrap_or_default().trim();
+        if !column.is_empty() {
+            columns.push(column.to_string());
+        }
+    }
+    columns
+}
+
+/// make insert sql of entities,return (sql,args,columns)
+/// skip_null_id: remove the id columns which value is null(let database generate it)
+/// for example: INSERT INTO biz_activity (id,name,version) VALUES ( ? , ? , ?),( ? , ? , ?)
+fn make_insert_sql<T>(driver_type: &DriverType, fill_plugin: &Option<Box<dyn FieldFill>>, args: &[T], skip_null_id: bool) -> Result<(String, Vec<Value>, Vec<String>)> where T: CRUDEnable {
+    let mut value_arr = String::new();
+    let mut arg_arr = vec![];
+    let mut fields = "".to_string();
+    let mut columns = vec![];
+    let mut field_index = 0;
+    for x in args {
+        let mut map = T::make_field_value_map(driver_type, fill_plugin, FillAction::Insert, x)?;
+        if skip_null_id {
+            for id_column in T::id_columns() {
+                if map.get(&id_column).unwrap_or(&Value::Null).is_null() {
+                    map.remove(&id_column);
+                }
+            }
+        }
+        if fields.is_empty() {
+            fields = T::make_fields(&map)?;
+            columns = map.keys().cloned().collect();
+        } else if columns.len() != map.len() {
+            return Err(Error::from("[rbatis] the insert columns of entities must be same!"));
+        }
+        let (values, args) = T::make_sql_arg(&mut field_index, driver_type, &map)?;
+        value_arr = value_arr + format!("({}),", values).as_str();
+        for x in args {
+            arg_arr.push(x);
+        }
+    }
+    value_arr.pop();//pop ','
+    let sql = format!("INSERT INTO {} ({}) VALUES {}", T::table_name(), fields, value_arr);
+    Ok((sql, arg_arr, columns))
+}
+
+/// the rows count of one batch insert sql,make sure the bind params not over DriverType.max_bind_params()
+fn make_batch_chunk_size<T>(driver_type: &DriverType, fill_plugin: &Option<Box<dyn FieldFill>>, first: &T) -> Result<usize> where T: CRUDEnable {
+    let columns = T::make_field_value_map(driver_type, fill_plugin, FillAction::Insert, first)?.len();
+    if columns == 0 {
+        return Err(Error::from("[rbatis] batch insert columns can not be empty!"));
+    }
+    let size = driver_type.max_bind_params()? / columns;
+    if size == 0 {
+        return Ok(1);
     }
-    if !where_sql.is_empty() {
-        sql = format!("SELECT {} FROM {} WHERE {}", T::table_fields(), T::table_name(), where_sql);
+    Ok(size)
+}
+
+/// the rows count of one update batch sql,every row will bind (id columns + 1) * columns + id columns params
+fn make_update_batch_chunk_size<T>(driver_type: &DriverType, fill_plugin: &Option<Box<dyn FieldFill>>, first: &T) -> Result<usize> where T: CRUDEnable {
+    let columns = T::make_field_value_map(driver_type, fill_plugin, FillAction::Update, first)?.len();
+    let id_len = T::id_columns().len();
+    let size = driver_type.max_bind_params()? / ((id_len + 1) * columns + id_len);
+    if size == 0 {
+        return Ok(1);
+    }
+    Ok(size)
+}
+
+/// make update sql of the arg by the rendered wrapper,return (sql wrapper,version value)
+/// for example: UPDATE biz_activity SET  name = ?, version = version + 1 WHERE id = ? AND version = ?
+fn make_update_sql<T>(driver_type: &DriverType, fill_plugin: &Option<Box<dyn FieldFill>>, arg: &T, w: &Wrapper, update_null_value: bool) -> Result<(Wrapper, Option<Value>)> where T: CRUDEnable {
+    let mut args = vec![];
+    let map = T::make_field_value_map(driver_type, fill_plugin, FillAction::Update, arg)?;
+    let id_columns = T::id_columns();
+    let version_column = T::version_column();
+    let mut version_value = None;
+    let mut sets = String::new();
+    for (k, v) in map {
+        //filter id
+        if id_columns.contains(&k) {
+            continue;
+        }
+        //version column: SET version = version + 1
+        if version_column.is_some() && k.eq(version_column.as_ref().unwrap()) {
+            if !v.is_null() {
+                sets.push_str(format!(" {} = {} + 1,", k, k).as_str());
+                version_value = Some(v);
+            }
+            continue;
+        }
+        //filter null
+        if !update_null_value && v.is_null() {
+            continue;
+        }
+        sets.push_str(format!(" {} = {},", k, driver_type.stmt_convert(args.len())).as_str());
+        args.push(v);
+    }
+    sets.pop();
+    let mut wrapper = Wrapper::new(driver_type);
+    wrapper.push(&format!("UPDATE {} SET {}", T::table_name(), sets), &args);
+    if !w.sql.is_empty() || version_value.is_some() {
+        wrapper.push_sql(" WHERE ");
+        if !w.sql.is_empty() {
+            wrapper.push_wrapper(w);
+        }
+        if version_value.is_some() {
+            wrapper.and().eq(version_column.as_ref().unwrap(), version_value.as_ref().unwrap());
+        }
+    }
+    return Ok((wrapper.check()?, version_value));
+}
+
+/// make one update sql of entities,return (sql,args). return empty sql if no column need update
+/// for example: UPDATE biz_activity SET name = CASE WHEN id = ? THEN ? WHEN id = ? THEN ? ELSE name END WHERE id IN (?,?)
+fn make_update_batch_sql<T>(driver_type: &DriverType, fill_plugin: &Option<Box<dyn FieldFill>>, args: &[T]) -> Result<(String, Vec<Value>)> where T: CRUDEnable {
+    let id_columns = T::id_columns();
+    let chains = T::format_chain();
+    let mut maps = vec![];
+    let mut columns: Vec<String> = vec![];
+    for x in args {
+        let map = T::make_field_value_map(driver_type, fill_plugin, FillAction::Update, x)?;
+        for (k, v) in &map {
+            if !id_columns.contains(k) && !v.is_null() && !columns.contains(k) {
+                columns.push(k.to_string());
+            }
+        }
+        let id_values = make_id_values(&id_columns, &make_id_value::<T>(&map)?)?;
+        if id_values.iter().any(|v| v.is_null()) {
+            return Err(Error::from(format!("[rbatis] update batch by id,the ({}) can not be null!", id_columns.join(","))));
+        }
+        maps.push((map, id_values));
+    }
+    if columns.is_empty() {
+        return Ok((String::new(), vec![]));
+    }
+    let mut arr = vec![];
+    let mut sets = String::new();
+    for column in &columns {
+        let mut case_sql = format!("{} = CASE", column);
+        for (map, id_values) in &maps {
+            let v = map.get(column).unwrap_or(&Value::Null);
+            if v.is_null() {
+                continue;
+            }
+            let mut when_sql = String::new();
+            for (id_column, id_value) in id_columns.iter().zip(id_values) {
+                when_sql.push_str(format!("{} = {} AND ", id_column, driver_type.stmt_convert(arr.len())).as_str());
+                arr.push(id_value.to_owned());
+            }
+            let when_sql = when_sql.trim_end_matches(" AND ");
+            let mut value_sql = driver_type.stmt_convert(arr.len());
+            for chain in &chains {
+                if chain.need_format(driver_type, column) {
+                    let (sql, _) = chain.do_format(driver_type, &value_sql, v)?;
+                    value_sql = sql;
+                }
+            }
+            case_sql.push_str(format!(" WHEN {} THEN {}", when_sql, value_sql).as_str());
+            arr.push(v.to_owned());
+        }
+        case_sql.push_str(format!(" ELSE {} END,", column).as_str());
+        sets.push_str(case_sql.as_str());
+    }
+    sets.pop();
+    let mut in_sql = String::new();
+    for (_, id_values) in &maps {
+        let mut item_sql = String::new();
+        for v in id_values {
+            item_sql.push_str(driver_type.stmt_convert(arr.len()).as_str());
+            item_sql.push_str(",");
+            arr.push(v.to_owned());
+        }
+        item_sql.pop();
+        if id_columns.len() == 1 {
+            in_sql.push_str(format!("{},", item_sql).as_str());
+        } else {
+            in_sql.push_str(format!("({}),", item_sql).as_str());
+        }
+    }
+    in_sql.pop();
+    let where_columns = match id_columns.len() {
+        1 => id_columns[0].to_string(),
+        _ => format!("({})", id_columns.join(","))
+    };
+    let sql = format!("UPDATE {} SET {} WHERE {} IN ({})", T::table_name(), sets, where_columns, in_sql);
+    Ok((sql, arr))
+}
+
+/// the generated id must be single column
+fn make_single_id_column<T>() -> Result<String> where T: CRUDEnable {
+    let mut id_columns = T::id_columns();
+    if id_columns.len() != 1 {
+        return Err(Error::from(format!("[rbatis] returning id only support single id column,but find ({})!", id_columns.join(","))));
+    }
+    Ok(id_columns.pop().unwrap())
+}
+
+/// decode the rows(for example: [{"id":1}]) into ids
+fn decode_ids<T>(rows: Vec<Value>) -> Result<Vec<T::IdType>> where T: CRUDEnable {
+    let mut ids = vec![];
+    for row in rows {
+        ids.push(rbatis_core::decode::json_decode::<T::IdType>(vec![row])?);
+    }
+    Ok(ids)
+}
+
+/// decode the auto increment id(LAST_INSERT_ID,last_insert_rowid) into IdType,the IdType must be an integer
+fn decode_last_id<T>(last_id: i64) -> Result<T::IdType> where T: CRUDEnable {
+    serde_json::from_value(json!(last_id))
+        .map_err(|_| Error::from(format!("[rbatis] the IdType of {} can not hold the auto increment id,please set the id before save!", T::table_name())))
+}
+
+/// make upsert sql,the default conflict columns is T::id_columns(),
+/// the default update columns is all columns without conflict columns.
+/// mysql use the table's primary/unique key,so the conflict columns must be empty
+fn make_upsert_sql<T>(driver_type: &DriverType, columns: &Vec<String>, conflict_columns: &[&str], update_columns: &[&str]) -> Result<String> where T: CRUDEnable {
+    if driver_type.eq(&DriverType::Mysql) && !conflict_columns.is_empty() {
+        return Err(Error::from("[rbatis] mysql upsert use the table's primary/unique key,the conflict columns must be empty!"));
+    }
+    let conflict_columns: Vec<String> = match conflict_columns.is_empty() {
+        true => T::id_columns(),
+        false => make_upsert_columns(driver_type, conflict_columns)?
+    };
+    let update_columns: Vec<String> = match update_columns.is_empty() {
+        true => columns.iter().filter(|x| !conflict_columns.contains(x)).cloned().collect(),
+        false => make_upsert_columns(driver_type, update_columns)?
+    };
+    driver_type.upsert_sql(&conflict_columns, &update_columns)
+}
+
+/// the conflict/update columns must be identifiers,same as the columns of Wrapper
+fn make_upsert_columns(driver_type: &DriverType, columns: &[&str]) -> Result<Vec<String>> {
+    let w = Wrapper::new(driver_type);
+    let mut result = Vec::with_capacity(columns.len());
+    for x in columns {
+        result.push(w.column_sql(driver_type, x)?);
+    }
+    Ok(result)
+}
+
+/// make remove sql,if the logic delete plugin is set,it will make an update sql
+fn make_remove_sql<T>(driver_type: &DriverType, logic_plugin: &Option<Box<dyn LogicDelete>>, where_sql: &str) -> Result<String> where T: CRUDEnable {
+    let table_plugin = make_logic_plugin::<T>(logic_plugin);
+    let logic_plugin = match table_plugin {
+        Some(_) => &table_plugin,
+        None => logic_plugin
+    };
+    if logic_plugin.is_some() {
+        return logic_plugin.as_ref().unwrap().create_remove_sql(driver_type, T::table_name().as_str(), &T::table_fields(), make_where_sql(where_sql).as_str());
+    }
+    Ok(format!("DELETE FROM {} {}", T::table_name(), make_where_sql(where_sql)))
+}
+
+/// the key of relation id,for example: 1 => "1", "1" => "\"1\"",None => None
+fn make_relation_key<I>(id: &Option<I>) -> Option<String> where I: Serialize {
+    match id {
+        Some(id) => serde_json::to_string(id).ok(),
+        None => None
+    }
+}
+
+/// group the items by key,the item which key is None will be ignore
+fn group_by_key<T, I, F>(items: Vec<T>, key: F) -> HashMap<String, Vec<T>> where I: Serialize, F: Fn(&T) -> Option<I> {
+    let mut groups: HashMap<String, Vec<T>> = HashMap::new();
+    for item in items {
+        let k = make_relation_key(&key(&item));
+        if k.is_some() {
+            groups.entry(k.unwrap()).or_insert(vec![]).push(item);
+        }
+    }
+    groups
+}
+
+/// make the logic delete plugin of table T::logic_delete_column(),return None if not set
+fn make_logic_plugin<T>(logic_plugin: &Option<Box<dyn LogicDelete>>) -> Option<Box<dyn LogicDelete>> where T: CRUDEnable {
+    let column = T::logic_delete_column()?;
+    let (deleted, un_deleted) = match logic_plugin {
+        Some(plugin) => (plugin.deleted(), plugin.un_deleted()),
+        None => (1, 0)
+    };
+    Some(Box::new(RbatisLogicDeletePlugin::new_opt(&column, deleted, un_deleted)))
+}
+
+/// make where wrapper of the table id,composite id will match id_columns() by position
+/// for example: id = ?  or  tenant_id = ? AND order_no = ?
+fn make_id_wrapper<T, I>(driver_type: &DriverType, id: &I) -> Result<Wrapper> where T: CRUDEnable, I: Serialize {
+    let id_columns = T::id_columns();
+    let id_values = make_id_values(&id_columns, id)?;
+    let mut w = Wrapper::new(driver_type);
+    for (column, value) in id_columns.iter().zip(id_values) {
+        w.and().eq(column, value);
+    }
+    return w.check();
+}
+
+/// make where wrapper of the table ids
+/// for example: id IN ( ? , ? )  or  (tenant_id,order_no) IN ((?,?),(?,?))
+fn make_ids_wrapper<T, I>(driver_type: &DriverType, ids: &[I]) -> Result<Wrapper> where T: CRUDEnable, I: Serialize {
+    let id_columns = T::id_columns();
+    let mut w = Wrapper::new(driver_type);
+    if id_columns.len() == 1 {
+        let mut values = vec![];
+        for id in ids {
+            values.append(&mut make_id_values(&id_columns, id)?);
+        }
+        w.in_array(&id_columns[0], &values);
+        return w.check();
+    }
+    w.push_sql(format!("({}) IN (", id_columns.join(",")).as_str());
+    for (index, id) in ids.iter().enumerate() {
+        if index != 0 {
+            w.push_sql(",");
+        }
+        w.push_sql("(");
+        for (i, v) in make_id_values(&id_columns, id)?.into_iter().enumerate() {
+            if i != 0 {
+                w.push_sql(",");
+            }
+            w.push_stmt_arg(v);
+        }
+        w.push_sql(")");
+    }
+    w.push_sql(")");
+    return w.check();
+}
+
+/// split the id into values of id columns
+fn make_id_values<I>(id_columns: &Vec<String>, id: &I) -> Result<Vec<Value>> where I: Serialize {
+    let v = serde_json::to_value(id).map_err(|e| Error::from(format!("[rbatis] id to json fail: {}", e)))?;
+    if id_columns.len() == 1 {
+        return Ok(vec![v]);
+    }
+    match v {
+        serde_json::Value::Array(arr) if arr.len() == id_columns.len() => Ok(arr),
+        _ => Err(Error::from(format!("[rbatis] composite id must be an array/tuple of ({})!", id_columns.join(","))))
+    }
+}
+
+/// fetch the id value from table field value map
+fn make_id_value<T>(map: &Map<String, Value>) -> Result<Value> where T: CRUDEnable {
+    let mut values = vec![];
+    for column in T::id_columns() {
+        let v = map.get(&column);
+        if v.is_none() {
+            return Err(Error::from(format!("[rbaits] arg not have \"{}\" field! ", column)));
+        }
+        values.push(v.unwrap().to_owned());
+    }
+    if values.len() == 1 {
+        return Ok(values.pop().unwrap());
+    }
+    return Ok(serde_json::Value::Array(values));
+}
+
+/// make select sql of table T,columns: the select columns,for example: T::table_fields() or "id,name"
+fn make_select_sql<T>(driver_type: &DriverType, logic_plugin: &Option<Box<dyn LogicDelete>>, columns: &str, join_sql: &str, where_sql: &str) -> Result<String> where T: CRUDEnable {
+    let table_plugin = make_logic_plugin::<T>(logic_plugin);
+    let logic_plugin = match table_plugin {
+        Some(_) => &table_plugin,
+        None => logic_plugin
+    };
+    if logic_plugin.is_some() {
+        let logic_ref = logic_plugin.as_ref().unwrap();
+        return logic_ref.create_select_sql(driver_type, &T::table_name(), join_sql, &T::table_fields(), columns, where_sql);
+    }
+    let table = format!("{}{}", T::table_name(), join_sql);
+    let mut sql = String::new();
+    let where_sql = where_sql.trim();
+    if where_sql.is_empty() {
+        sql = format!("SELECT {} FROM {}", columns, table);
+    } else if where_sql.starts_with("ORDER BY") || where_sql.starts_with("GROUP BY") {
+        sql = format!("SELECT {} FROM {} {}", columns, table, where_sql);
     } else {
-        sql = format!("SELECT {} FROM {}", T::table_fields(), T::table_name());
+        sql = format!("SELECT {} FROM {} WHERE {}", columns, table, where_sql);
+    }
+    Ok(sql)
+}
+
+/// make select sql of wrapper(select columns,joins,where,limit)
+/// the columns arg is first,then the wrapper select columns,then all table fields
+fn make_wrapper_select_sql<T>(driver_type: &DriverType, logic_plugin: &Option<Box<dyn LogicDelete>>, columns: Option<&str>, w: &Wrapper, with_limit: bool) -> Result<String> where T: CRUDEnable {
+    let columns = match (columns, &w.select_columns) {
+        (Some(columns), _) => columns.to_string(),
+        (None, Some(columns)) => columns.clone(),
+        (None, None) => T::table_fields()
+    };
+    let mut sql = make_select_sql::<T>(driver_type, logic_plugin, &columns, &w.join_sql, &w.sql)?;
+    if with_limit {
+        sql.push_str(w.limit_sql()?.as_str());
     }
     Ok(sql)
 }
 
+/// the count/sum... of wrapper,ignore the order by,limit and offset
+fn make_aggregate_wrapper(w: &Wrapper) -> Result<Wrapper> {
+    let mut w = w.clone();
+    w.limit = None;
+    w.offset = None;
+    w.trim_order_by();
+    w.check()
+}
+
+/// make aggregate column,for example: sum(status),the column is checked(and quoted) by the wrapper
+fn make_aggregate_column(driver_type: &DriverType, w: &Wrapper, func: &str, column: &str) -> Result<String> {
+    if column.trim().is_empty() {
+        return Err(Error::from(format!("[rbatis] {}() column can not be empty!", func)));
+    }
+    Ok(format!("{}({})", func, w.column_sql(driver_type, column)?))
+}
+
+/// join the select columns,for example: ["id","name"] => "id,name"
+fn make_columns(columns: &[&str]) -> Result<String> {
+    if columns.is_empty() {
+        return Err(Error::from("[rbatis] select columns can not be empty!"));
+    }
+    Ok(columns.join(","))
+}
+
 mod test {
     use chrono::{DateTime, Utc};
     use fast_log::log::RuntimeType;
@@ -430,8 +1240,13 @@ mod test {
 
     use rbatis_core::Error;
 
-    use crate::crud::{CRUD, CRUDEnable, Id, Ids};
-    use crate::plugin::logic_delete::RbatisLogicDeletePlugin;
+    use rbatis_core::db::{DriverType, TIMESTAMP_ARG};
+    use crate::sql::BatchLimit;
+
+    use crate::crud::{BelongsTo, CRUD, CRUDEnable, HasMany, Id, Ids, decode_ids, make_batch_chunk_size, make_id_wrapper, make_ids_wrapper, make_insert_sql, group_by_key, make_aggregate_column, make_relation_key, make_remove_sql, make_select_sql, make_update_batch_sql, make_update_sql, make_upsert_sql, make_aggregate_wrapper, make_wrapper_select_sql};
+    use crate::plugin::fill::{FieldFill, RbatisTimeFillPlugin};
+    use crate::plugin::intercept::SqlIntercept;
+    use crate::plugin::logic_delete::{LogicDelete, RbatisLogicDeletePlugin};
     use crate::plugin::page::{Page, PageRequest};
     use crate::rbatis::Rbatis;
     use crate::wrapper::Wrapper;
@@ -480,6 +1295,566 @@ mod test {
     }
 
 
+    #[derive(Serialize, Deserialize, Clone, Debug)]
+    pub struct BizActivityVersion {
+        pub id: Option<String>,
+        pub name: Option<String>,
+        pub version: Option<i32>,
+    }
+
+    impl CRUDEnable for BizActivityVersion {
+        type IdType = String;
+        fn table_name() -> String {
+            "biz_activity".to_string()
+        }
+        fn version_column() -> Option<String> {
+            Some("version".to_string())
+        }
+    }
+
+
+    #[derive(Serialize, Deserialize, Clone, Debug)]
+    pub struct BizOrder {
+        pub tenant_id: Option<i64>,
+        pub order_no: Option<String>,
+        pub remark: Option<String>,
+    }
+
+    impl CRUDEnable for BizOrder {
+        type IdType = (i64, String);
+        fn id_columns() -> Vec<String> {
+            vec!["tenant_id".to_string(), "order_no".to_string()]
+        }
+    }
+
+    /// remove the sqlite db file and the wal files
+    /// create an temp sqlite db file and link it,remove it by remove_db() after the rb drop
+    async fn link_temp_db(rb: &Rbatis) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("rbatis_crud_{}.db", uuid::Uuid::new_v4()));
+        std::fs::File::create(&path).unwrap();
+        rb.link(&format!("sqlite://{}", path.to_str().unwrap())).await.unwrap();
+        path
+    }
+
+    fn remove_db(path: &std::path::Path) {
+        let path = path.to_str().unwrap();
+        for file in &[path.to_string(), format!("{}-wal", path), format!("{}-shm", path)] {
+            let _ = std::fs::remove_file(file);
+        }
+    }
+
+    #[test]
+    pub fn test_save_batch_in_tx() {
+        async_std::task::block_on(async {
+            let rb = Rbatis::new();
+            let path = link_temp_db(&rb).await;
+            rb.exec("", "CREATE TABLE biz_activity (id TEXT PRIMARY KEY, name TEXT)").await.unwrap();
+            let a = BizActivityNoDel { id: Some("1".to_string()), name: Some("a".to_string()) };
+            //the insert error is return,and the tx is rollback
+            let r = rb.save_batch_in_tx(&[a.clone(), a.clone()]).await;
+            assert!(r.err().unwrap().to_string().contains("UNIQUE"));
+            assert!(rb.tx_context.is_empty());
+            let b = BizActivityNoDel { id: Some("2".to_string()), name: Some("b".to_string()) };
+            assert_eq!(rb.save_batch_in_tx(&[a, b]).await.unwrap(), 2);
+            let rows: Vec<BizActivityNoDel> = rb.list("").await.unwrap();
+            assert_eq!(rows.len(), 2);
+            drop(rb);
+            remove_db(&path);
+        });
+    }
+
+    #[test]
+    pub fn test_composite_id_wrapper() {
+        let w = make_id_wrapper::<BizOrder, _>(&DriverType::Postgres, &(1, "NO.1".to_string())).unwrap();
+        assert_eq!(w.sql, "tenant_id = $1 AND order_no = $2");
+        assert_eq!(w.args, vec![json!(1), json!("NO.1")]);
+
+        let w = make_ids_wrapper::<BizOrder, _>(&DriverType::Postgres, &[(1, "NO.1".to_string()), (2, "NO.2".to_string())]).unwrap();
+        assert_eq!(w.sql, "(tenant_id,order_no) IN (($1,$2),($3,$4))");
+        assert_eq!(w.args.len(), 4);
+
+        let w = make_id_wrapper::<BizOrder, _>(&DriverType::Mysql, &1);
+        assert!(w.is_err());
+
+        //map key must be a string,the id can not serialize to json
+        let mut id = std::collections::HashMap::new();
+        id.insert(vec![1], 1);
+        assert!(make_id_wrapper::<BizActivity, _>(&DriverType::Mysql, &id).is_err());
+        assert!(make_ids_wrapper::<BizActivity, _>(&DriverType::Mysql, &[id]).is_err());
+    }
+
+    #[test]
+    pub fn test_remove_by_string_id_sql() {
+        let id = "1' OR '1' = '1".to_string();
+        let cases = vec![(DriverType::Mysql, "?"), (DriverType::Postgres, "$1"), (DriverType::Sqlite, "?")];
+        for (driver_type, placeholder) in cases {
+            let w = make_id_wrapper::<BizActivity, _>(&driver_type, &id).unwrap();
+            assert_eq!(w.args, vec![json!(id)]);
+
+            let sql = make_remove_sql::<BizActivity>(&driver_type, &None, &w.sql).unwrap();
+            assert_eq!(sql.trim(), format!("DELETE FROM biz_activity  WHERE id = {}", placeholder));
+
+            let logic_plugin: Option<Box<dyn LogicDelete>> = Some(Box::new(RbatisLogicDeletePlugin::new("delete_flag")));
+            let sql = make_remove_sql::<BizActivity>(&driver_type, &logic_plugin, &w.sql).unwrap();
+            assert_eq!(sql.trim(), format!("UPDATE biz_activity SET delete_flag = 1 WHERE id = {}", placeholder));
+            assert!(!sql.contains(&id));
+        }
+    }
+
+    #[test]
+    pub fn test_upsert_sql() {
+        let order = BizOrder {
+            tenant_id: Some(1),
+            order_no: Some("NO.1".to_string()),
+            remark: None,
+        };
+        let (mut sql, args, columns) = make_insert_sql(&DriverType::Postgres, &None, &[order.clone(), order], false).unwrap();
+        sql.push_str(make_upsert_sql::<BizOrder>(&DriverType::Postgres, &columns, &[], &[]).unwrap().as_str());
+        println!("{}", sql);
+        assert_eq!(sql, "INSERT INTO biz_order (order_no,remark,tenant_id) VALUES ($1,$2,$3),($4,$5,$6) ON CONFLICT (tenant_id,order_no) DO UPDATE SET remark = excluded.remark");
+        assert_eq!(args.len(), 6);
+
+        let sql = make_upsert_sql::<BizOrder>(&DriverType::Sqlite, &columns, &["order_no"], &["remark"]).unwrap();
+        assert_eq!(sql, " ON CONFLICT (order_no) DO UPDATE SET remark = excluded.remark");
+        let sql = make_upsert_sql::<BizOrder>(&DriverType::Mysql, &columns, &[], &["remark"]).unwrap();
+        assert_eq!(sql, " ON DUPLICATE KEY UPDATE remark = VALUES(remark)");
+        //mysql can not set the conflict columns
+        assert!(make_upsert_sql::<BizOrder>(&DriverType::Mysql, &columns, &["order_no"], &[]).is_err());
+        //not an identifier
+        assert!(make_upsert_sql::<BizOrder>(&DriverType::Postgres, &columns, &["id) DO NOTHING;--"], &[]).is_err());
+        assert!(make_upsert_sql::<BizOrder>(&DriverType::Postgres, &columns, &[], &["remark = 1,name"]).is_err());
+    }
+
+    #[test]
+    pub fn test_save_returning_id_sql() {
+        let activity = BizActivityVersion {
+            id: None,
+            name: Some("test".to_string()),
+            version: Some(1),
+        };
+        let (sql, args, columns) = make_insert_sql(&DriverType::Postgres, &None, &[activity], true).unwrap();
+        assert_eq!(sql, "INSERT INTO biz_activity (name,version) VALUES ($1,$2)");
+        assert_eq!(args.len(), 2);
+
+        let ids: Vec<String> = decode_ids::<BizActivityVersion>(vec![json!({"id": 1}), json!({"id": 2})]).unwrap();
+        assert_eq!(ids, vec!["1".to_string(), "2".to_string()]);
+    }
+
+    #[test]
+    pub fn test_save_returning_id_sqlite() {
+        #[derive(Serialize, Deserialize, Clone, Debug)]
+        pub struct BizLog {
+            pub id: Option<i64>,
+            pub name: Option<String>,
+        }
+        impl CRUDEnable for BizLog {
+            type IdType = i64;
+        }
+        async_std::task::block_on(async {
+            let rb = Rbatis::new();
+            let path = link_temp_db(&rb).await;
+            rb.exec("", "CREATE TABLE biz_log (id INTEGER PRIMARY KEY, name TEXT)").await.unwrap();
+            rb.exec("", "CREATE TABLE biz_activity (id TEXT PRIMARY KEY, name TEXT)").await.unwrap();
+            //auto increment id
+            assert_eq!(rb.save_returning_id("", &BizLog { id: None, name: Some("a".to_string()) }).await.unwrap(), 1);
+            //the supplied id,not the rowid
+            assert_eq!(rb.save_returning_id("", &BizLog { id: Some(10), name: Some("b".to_string()) }).await.unwrap(), 10);
+            let a = BizActivityNoDel { id: Some("a1".to_string()), name: Some("a".to_string()) };
+            assert_eq!(rb.save_returning_id("", &a).await.unwrap(), "a1".to_string());
+            //String id can not be auto increment,not insert
+            let b = BizActivityNoDel { id: None, name: Some("b".to_string()) };
+            assert!(rb.save_returning_id("", &b).await.is_err());
+            assert_eq!(rb.count_by_wrapper::<BizActivityNoDel>("", &Wrapper::new(&DriverType::Sqlite)).await.unwrap(), 1);
+            drop(rb);
+            remove_db(&path);
+        });
+    }
+
+    #[test]
+    pub fn test_batch_chunk_size() {
+        let order = BizOrder {
+            tenant_id: Some(1),
+            order_no: Some("NO.1".to_string()),
+            remark: None,
+        };
+        assert_eq!(make_batch_chunk_size(&DriverType::Sqlite, &None, &order).unwrap(), 333);
+        assert_eq!(make_batch_chunk_size(&DriverType::Postgres, &None, &order).unwrap(), 21845);
+
+        let orders = vec![order; 1000];
+        let chunks: Vec<usize> = orders.chunks(333).map(|x| x.len()).collect();
+        assert_eq!(chunks, vec![333, 333, 333, 1]);
+        let (_, args, _) = make_insert_sql(&DriverType::Sqlite, &None, &orders[0..333], false).unwrap();
+        assert!(args.len() <= DriverType::Sqlite.max_bind_params().unwrap());
+    }
+
+    #[test]
+    pub fn test_update_batch_sql() {
+        let orders = vec![BizOrder {
+            tenant_id: Some(1),
+            order_no: Some("NO.1".to_string()),
+            remark: Some("a".to_string()),
+        }, BizOrder {
+            tenant_id: Some(1),
+            order_no: Some("NO.2".to_string()),
+            remark: None,
+        }];
+        let (sql, args) = make_update_batch_sql(&DriverType::Postgres, &None, &orders).unwrap();
+        assert_eq!(sql, "UPDATE biz_order SET remark = CASE WHEN tenant_id = $1 AND order_no = $2 THEN $3 ELSE remark END WHERE (tenant_id,order_no) IN (($4,$5),($6,$7))");
+        assert_eq!(args.len(), 7);
+
+        let activities = vec![BizActivityVersion {
+            id: Some("1".to_string()),
+            name: Some("a".to_string()),
+            version: None,
+        }, BizActivityVersion {
+            id: Some("2".to_string()),
+            name: Some("b".to_string()),
+            version: None,
+        }];
+        let (sql, _) = make_update_batch_sql(&DriverType::Mysql, &None, &activities).unwrap();
+        assert_eq!(sql, "UPDATE biz_activity SET name = CASE WHEN id = ? THEN ? WHEN id = ? THEN ? ELSE name END WHERE id IN (?,?)");
+    }
+
+    #[test]
+    pub fn test_select_columns_sql() {
+        let sql = make_select_sql::<BizActivity>(&DriverType::Mysql, &None, "id,name", "", "id = ?").unwrap();
+        assert_eq!(sql, "SELECT id,name FROM biz_activity WHERE id = ?");
+
+        let plugin: Option<Box<dyn LogicDelete>> = Some(Box::new(RbatisLogicDeletePlugin::new("delete_flag")));
+        let sql = make_select_sql::<BizActivity>(&DriverType::Mysql, &plugin, "id,name", "", "id = ?").unwrap();
+        assert_eq!(sql, "SELECT id,name FROM biz_activity WHERE delete_flag = 0 AND id = ?");
+        let sql = make_select_sql::<BizActivity>(&DriverType::Mysql, &plugin, &BizActivity::table_fields(), "", "").unwrap();
+        assert_eq!(sql, format!("SELECT {} FROM biz_activity WHERE delete_flag = 0", BizActivity::table_fields()));
+    }
+
+    #[test]
+    pub fn test_wrapper_select_sql() {
+        let w = Wrapper::new(&DriverType::Mysql)
+            .select(&["biz_activity.*", "o.amount"])
+            .left_join("biz_order o", "o.activity_id", "biz_activity.id")
+            .eq("o.status", 1)
+            .order_by(false, &["o.amount"])
+            .limit(10)
+            .offset(20)
+            .check().unwrap();
+        let sql = make_wrapper_select_sql::<BizActivity>(&DriverType::Mysql, &None, None, &w, true).unwrap();
+        assert_eq!(sql, "SELECT biz_activity.*,o.amount FROM biz_activity LEFT JOIN biz_order o ON o.activity_id = biz_activity.id WHERE o.status = ? ORDER BY o.amount DESC LIMIT 20,10");
+        let sql = make_wrapper_select_sql::<BizActivity>(&DriverType::Mysql, &None, Some("count(1)"), &make_aggregate_wrapper(&w).unwrap(), true).unwrap();
+        assert_eq!(sql, "SELECT count(1) FROM biz_activity LEFT JOIN biz_order o ON o.activity_id = biz_activity.id WHERE o.status = ?");
+        let w = Wrapper::new(&DriverType::Mysql).is_null("name").order_by(true, &["id"]).check().unwrap();
+        assert_eq!(make_aggregate_wrapper(&w).unwrap().sql, "name IS NULL");
+
+        let plugin: Option<Box<dyn LogicDelete>> = Some(Box::new(RbatisLogicDeletePlugin::new("delete_flag")));
+        let w = Wrapper::new(&DriverType::Postgres).order_by(true, &["id"]).limit(5).check().unwrap();
+        let sql = make_wrapper_select_sql::<BizActivity>(&DriverType::Postgres, &plugin, Some("id"), &w, true).unwrap();
+        assert_eq!(sql, "SELECT id FROM biz_activity WHERE delete_flag = 0 ORDER BY id ASC LIMIT 5 OFFSET 0");
+        let sql = make_wrapper_select_sql::<BizActivity>(&DriverType::Postgres, &None, Some("id"), &w, false).unwrap();
+        assert_eq!(sql, "SELECT id FROM biz_activity ORDER BY id ASC");
+
+        let w = Wrapper::new(&DriverType::Mysql)
+            .left_join("biz_order o", "o.activity_id", "biz_activity.id")
+            .eq("o.status", 1)
+            .check().unwrap();
+        let sql = make_wrapper_select_sql::<BizActivity>(&DriverType::Mysql, &plugin, Some("biz_activity.id"), &w, false).unwrap();
+        assert_eq!(sql, "SELECT biz_activity.id FROM biz_activity LEFT JOIN biz_order o ON o.activity_id = biz_activity.id WHERE biz_activity.delete_flag = 0 AND o.status = ?");
+    }
+
+    #[test]
+    pub fn test_aggregate_sql() {
+        let plugin: Option<Box<dyn LogicDelete>> = Some(Box::new(RbatisLogicDeletePlugin::new("delete_flag")));
+        let sql = make_select_sql::<BizActivity>(&DriverType::Postgres, &plugin, "count(1)", "", "name = $1").unwrap();
+        assert_eq!(sql, "SELECT count(1) FROM biz_activity WHERE delete_flag = 0 AND name = $1");
+        let w = Wrapper::new(&DriverType::Postgres);
+        let column = make_aggregate_column(&DriverType::Postgres, &w, "sum", "status").unwrap();
+        let sql = make_select_sql::<BizActivity>(&DriverType::Postgres, &None, &column, "", "").unwrap();
+        assert_eq!(sql, "SELECT sum(status) FROM biz_activity");
+        assert!(make_aggregate_column(&DriverType::Postgres, &w, "max", " ").is_err());
+        assert!(make_aggregate_column(&DriverType::Postgres, &w, "max", "status) FROM biz_activity;--").is_err());
+        let w = Wrapper::new(&DriverType::Mysql).quote_columns().strict_columns(&BizActivity::table_fields()).check().unwrap();
+        assert_eq!(make_aggregate_column(&DriverType::Mysql, &w, "max", "status").unwrap(), "max(`status`)");
+        assert!(make_aggregate_column(&DriverType::Mysql, &w, "max", "amount").is_err());
+    }
+
+    #[test]
+    pub fn test_count_by_wrapper() {
+        async_std::task::block_on(async {
+            let mut rb = Rbatis::new();
+            rb.logic_plugin = Some(Box::new(RbatisLogicDeletePlugin::new("delete_flag")));
+            let path = link_temp_db(&rb).await;
+            rb.exec("", "CREATE TABLE biz_activity (id TEXT PRIMARY KEY, status INTEGER, version INTEGER, delete_flag INTEGER)").await.unwrap();
+            rb.exec("", "INSERT INTO biz_activity (id,status,version,delete_flag) VALUES ('1',1,1,0),('2',1,2,0),('3',0,3,0),('4',1,4,1)").await.unwrap();
+            let w = Wrapper::new(&rb.driver_type().unwrap()).eq("status", 1).check().unwrap();
+            //the deleted row is not count
+            assert_eq!(rb.count_by_wrapper::<BizActivity>("", &w).await.unwrap(), 2);
+            assert_eq!(rb.exists_by_wrapper::<BizActivity>("", &w).await.unwrap(), true);
+            assert_eq!(rb.sum_by_wrapper::<BizActivity, Option<i64>>("", &w, "version").await.unwrap(), Some(3));
+            let w = Wrapper::new(&rb.driver_type().unwrap()).eq("status", 2).check().unwrap();
+            assert_eq!(rb.count_by_wrapper::<BizActivity>("", &w).await.unwrap(), 0);
+            assert_eq!(rb.exists_by_wrapper::<BizActivity>("", &w).await.unwrap(), false);
+            //in_array empty match nothing,in_array_opt empty is ignored
+            let ids: Vec<String> = vec![];
+            let w = Wrapper::new(&rb.driver_type().unwrap()).in_array("id", &ids).check().unwrap();
+            assert_eq!(rb.count_by_wrapper::<BizActivity>("", &w).await.unwrap(), 0);
+            let w = Wrapper::new(&rb.driver_type().unwrap()).in_array_opt("id", Some(ids.as_slice())).check().unwrap();
+            assert_eq!(rb.count_by_wrapper::<BizActivity>("", &w).await.unwrap(), 3);
+            drop(rb);
+            remove_db(&path);
+        });
+    }
+
+    #[derive(Serialize, Deserialize, Clone, Debug)]
+    pub struct BizUser {
+        pub id: Option<i64>,
+        pub name: Option<String>,
+        pub login_count: Option<i32>,
+        pub del: Option<i32>,
+    }
+
+    impl CRUDEnable for BizUser {
+        type IdType = i64;
+
+        fn logic_delete_column() -> Option<String> {
+            Some("del".to_string())
+        }
+
+        fn skip_fields() -> Vec<String> {
+            vec!["login_count".to_string()]
+        }
+
+        fn column_renames() -> Vec<(String, String)> {
+            vec![("name".to_string(), "user_name".to_string())]
+        }
+    }
+
+    #[test]
+    pub fn test_table_columns() {
+        assert_eq!(BizUser::table_fields(), " del,id,user_name AS name ");
+        let user = BizUser {
+            id: Some(1),
+            name: Some("a".to_string()),
+            login_count: Some(1),
+            del: None,
+        };
+        let (sql, _, _) = make_insert_sql(&DriverType::Mysql, &None, &[user], true).unwrap();
+        assert_eq!(sql, "INSERT INTO biz_user (del,id,user_name) VALUES (?,?,?)");
+
+        let sql = make_select_sql::<BizUser>(&DriverType::Mysql, &None, &BizUser::table_fields(), "", "id = ?").unwrap();
+        assert_eq!(sql, "SELECT  del,id,user_name AS name  FROM biz_user WHERE del = 0 AND id = ?");
+        let plugin: Option<Box<dyn LogicDelete>> = Some(Box::new(RbatisLogicDeletePlugin::new_opt("delete_flag", 0, 1)));
+        let sql = make_remove_sql::<BizUser>(&DriverType::Mysql, &plugin, "id = ?").unwrap();
+        assert_eq!(sql.trim(), "UPDATE biz_user SET del = 0 WHERE id = ?");
+    }
+
+    #[test]
+    pub fn test_fill_plugin() {
+        let plugin: Option<Box<dyn FieldFill>> = Some(Box::new(RbatisTimeFillPlugin::default()));
+        let activity = BizActivity {
+            id: Some("12312".to_string()),
+            name: None,
+            pc_link: None,
+            h5_link: None,
+            pc_banner_img: None,
+            h5_banner_img: None,
+            sort: None,
+            status: Some(1),
+            remark: None,
+            create_time: None,
+            version: Some(1),
+            delete_flag: Some(1),
+        };
+        let (_, args, columns) = make_insert_sql(&DriverType::Mysql, &plugin, &[activity.clone()], false).unwrap();
+        let index = columns.iter().position(|x| x.eq("create_time")).unwrap();
+        assert!(args[index].get(TIMESTAMP_ARG).unwrap().is_string());
+
+        let (sql, _) = make_update_batch_sql(&DriverType::Mysql, &plugin, &[activity]).unwrap();
+        assert!(!sql.contains("create_time"));
+    }
+
+    #[test]
+    pub fn test_fill_plugin_save() {
+        #[derive(Serialize, Deserialize, Clone, Debug)]
+        pub struct BizLog {
+            pub id: Option<i64>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            pub create_time: Option<String>,
+        }
+        impl CRUDEnable for BizLog {
+            type IdType = i64;
+            fn table_fields() -> String {
+                "id,create_time".to_string()
+            }
+        }
+        async_std::task::block_on(async {
+            let mut rb = Rbatis::new();
+            rb.fill_plugin = Some(Box::new(RbatisTimeFillPlugin::default()));
+            let path = link_temp_db(&rb).await;
+            rb.exec("", "CREATE TABLE biz_log (id INTEGER PRIMARY KEY, create_time TEXT)").await.unwrap();
+            rb.save("", &BizLog { id: Some(1), create_time: None }).await.unwrap();
+            let log: BizLog = rb.fetch_by_id("", &1).await.unwrap();
+            assert_eq!(log.create_time.unwrap().len(), "2020-02-09 00:00:00".len());
+            drop(rb);
+            remove_db(&path);
+        });
+    }
+
+    #[test]
+    pub fn test_group_by_key() {
+        let orders = vec![BizOrder {
+            tenant_id: Some(1),
+            order_no: Some("NO.1".to_string()),
+            remark: None,
+        }, BizOrder {
+            tenant_id: Some(1),
+            order_no: Some("NO.2".to_string()),
+            remark: None,
+        }, BizOrder {
+            tenant_id: None,
+            order_no: Some("NO.3".to_string()),
+            remark: None,
+        }];
+        let groups = group_by_key(orders, |x| x.tenant_id);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups.get("1").unwrap().len(), 2);
+        assert_eq!(make_relation_key(&Some("1".to_string())), Some("\"1\"".to_string()));
+        assert_eq!(make_relation_key::<i64>(&None), None);
+    }
+
+    #[test]
+    pub fn test_load_relations() {
+        #[derive(Serialize, Deserialize, Clone, Debug)]
+        pub struct BizMember {
+            pub id: Option<i64>,
+            pub name: Option<String>,
+            #[serde(skip)]
+            pub orders: Option<Vec<BizMemberOrder>>,
+        }
+        impl CRUDEnable for BizMember {
+            type IdType = i64;
+            fn table_fields() -> String {
+                "id,name".to_string()
+            }
+        }
+        impl Id for BizMember {
+            type IdType = i64;
+            fn get_id(&self) -> Option<i64> {
+                self.id
+            }
+        }
+        impl HasMany<BizMemberOrder> for BizMember {
+            fn foreign_column() -> String {
+                "member_id".to_string()
+            }
+            fn foreign_key(child: &BizMemberOrder) -> Option<i64> {
+                child.member_id
+            }
+            fn set_children(&mut self, children: Vec<BizMemberOrder>) {
+                self.orders = Some(children);
+            }
+        }
+
+        #[derive(Serialize, Deserialize, Clone, Debug)]
+        pub struct BizMemberOrder {
+            pub id: Option<i64>,
+            pub member_id: Option<i64>,
+            #[serde(skip)]
+            pub member: Option<BizMember>,
+        }
+        impl CRUDEnable for BizMemberOrder {
+            type IdType = i64;
+            fn table_fields() -> String {
+                "id,member_id".to_string()
+            }
+        }
+        impl BelongsTo<BizMember> for BizMemberOrder {
+            fn parent_id(&self) -> Option<i64> {
+                self.member_id
+            }
+            fn set_parent(&mut self, parent: Option<BizMember>) {
+                self.member = parent;
+            }
+        }
+
+        /// record the query sql
+        struct SqlLog(std::sync::Arc<std::sync::Mutex<Vec<String>>>);
+        impl SqlIntercept for SqlLog {
+            fn name(&self) -> &str {
+                "SqlLog"
+            }
+            fn do_intercept(&self, _rb: &Rbatis, sql: &mut String, _args: &mut Vec<serde_json::Value>, _is_prepared_sql: bool) -> Result<(), Error> {
+                self.0.lock().unwrap().push(sql.clone());
+                Ok(())
+            }
+        }
+
+        async_std::task::block_on(async {
+            let sqls = std::sync::Arc::new(std::sync::Mutex::new(vec![]));
+            let mut rb = Rbatis::new();
+            let path = link_temp_db(&rb).await;
+            rb.exec("", "CREATE TABLE biz_member (id INTEGER PRIMARY KEY, name TEXT)").await.unwrap();
+            rb.exec("", "CREATE TABLE biz_member_order (id INTEGER PRIMARY KEY, member_id INTEGER)").await.unwrap();
+            rb.exec("", "INSERT INTO biz_member (id,name) VALUES (1,'a'),(2,'b'),(3,'c')").await.unwrap();
+            //member 1 have 2 orders,member 3 have no order,the member of order 4 not exist
+            rb.exec("", "INSERT INTO biz_member_order (id,member_id) VALUES (1,1),(2,1),(3,2),(4,9)").await.unwrap();
+            rb.sql_intercepts.push(Box::new(SqlLog(sqls.clone())));
+
+            let mut members: Vec<BizMember> = rb.list_by_ids("", &[1, 2, 3]).await.unwrap();
+            sqls.lock().unwrap().clear();
+            rb.load_has_many::<BizMember, BizMemberOrder>("", &mut members).await.unwrap();
+            assert_eq!(sqls.lock().unwrap().len(), 1);
+            assert!(sqls.lock().unwrap()[0].contains("FROM biz_member_order WHERE member_id IN"));
+            let order_ids: Vec<Vec<i64>> = members.iter().map(|x| x.orders.as_ref().unwrap().iter().map(|o| o.id.unwrap()).collect()).collect();
+            assert_eq!(order_ids, vec![vec![1, 2], vec![3], vec![]]);
+
+            let mut orders: Vec<BizMemberOrder> = rb.list("").await.unwrap();
+            sqls.lock().unwrap().clear();
+            rb.load_belongs_to::<BizMemberOrder, BizMember>("", &mut orders).await.unwrap();
+            //the duplicate member_id query once
+            assert_eq!(sqls.lock().unwrap().len(), 1);
+            assert!(sqls.lock().unwrap()[0].contains("FROM biz_member WHERE id IN ( ? , ? , ? )"));
+            let member_ids: Vec<Option<i64>> = orders.iter().map(|x| x.member.as_ref().and_then(|u| u.id)).collect();
+            assert_eq!(member_ids, vec![Some(1), Some(1), Some(2), None]);
+            drop(rb);
+            remove_db(&path);
+        });
+    }
+
+    #[test]
+    pub fn test_stream_by_wrapper() {
+        async_std::task::block_on(async {
+            use async_std::stream::StreamExt;
+            let rb = Rbatis::new();
+            let path = link_temp_db(&rb).await;
+            rb.exec("", "CREATE TABLE biz_activity (id TEXT PRIMARY KEY, name TEXT)").await.unwrap();
+            let rows: Vec<BizActivityNoDel> = (1..=3).map(|i| BizActivityNoDel { id: Some(i.to_string()), name: Some(format!("a{}", i)) }).collect();
+            rb.save_batch("", &rows).await.unwrap();
+            let w = Wrapper::new(&rb.driver_type().unwrap()).order_by(true, &["id"]).check().unwrap();
+
+            let mut stream = rb.stream_by_wrapper::<BizActivityNoDel>("", &w).await.unwrap();
+            let mut items = vec![];
+            while let Some(item) = stream.next().await {
+                items.push(item.unwrap());
+            }
+            drop(stream);
+            assert_eq!(serde_json::to_value(&items).unwrap(), serde_json::to_value(&rows).unwrap());
+
+            //the tx is taken by the stream,put back when the stream finish
+            rb.begin("1").await.unwrap();
+            let mut stream = rb.stream_by_wrapper::<BizActivityNoDel>("1", &w).await.unwrap();
+            assert!(!rb.tx_context.contains_key("1"));
+            let mut count = 0;
+            while let Some(item) = stream.next().await {
+                item.unwrap();
+                count += 1;
+            }
+            assert_eq!(count, 3);
+            assert!(rb.tx_context.contains_key("1"));
+            drop(stream);
+            rb.commit("1").await.unwrap();
+            drop(rb);
+            remove_db(&path);
+        });
+    }
+
     #[test]
     pub fn test_ids() {
         let vec = vec![BizActivity {
@@ -699,4 +2074,73 @@ mod test {
             println!("{}", serde_json::to_string(&r).unwrap());
         });
     }
+
+    #[test]
+    pub fn test_update_sql_version() {
+        let w = make_id_wrapper::<BizActivityVersion, _>(&DriverType::Mysql, &"1".to_string()).unwrap();
+        let activity = BizActivityVersion {
+            id: Some("1".to_string()),
+            name: Some("test_version".to_string()),
+            version: Some(1),
+        };
+        let (sql, version) = make_update_sql(&DriverType::Mysql, &None, &activity, &w, false).unwrap();
+        assert_eq!(sql.sql, "UPDATE biz_activity SET  name = ?, version = version + 1 WHERE id = ? AND version = ?");
+        assert_eq!(sql.args, vec![json!("test_version"), json!("1"), json!(1)]);
+        assert_eq!(version, Some(json!(1)));
+
+        //null version: no optimistic lock
+        let activity = BizActivityVersion {
+            version: None,
+            ..activity
+        };
+        let (sql, version) = make_update_sql(&DriverType::Mysql, &None, &activity, &w, false).unwrap();
+        assert_eq!(sql.sql, "UPDATE biz_activity SET  name = ? WHERE id = ?");
+        assert_eq!(sql.args, vec![json!("test_version"), json!("1")]);
+        assert_eq!(version, None);
+
+        //no version column: the version is a plain column
+        let activity = BizActivity {
+            id: Some("1".to_string()),
+            name: Some("test_version".to_string()),
+            pc_link: None,
+            h5_link: None,
+            pc_banner_img: None,
+            h5_banner_img: None,
+            sort: None,
+            status: None,
+            remark: None,
+            create_time: None,
+            version: Some(1),
+            delete_flag: None,
+        };
+        let w = make_id_wrapper::<BizActivity, _>(&DriverType::Postgres, &"1".to_string()).unwrap();
+        let (sql, version) = make_update_sql(&DriverType::Postgres, &None, &activity, &w, false).unwrap();
+        assert_eq!(sql.sql, "UPDATE biz_activity SET  name = $1, version = $2 WHERE id = $3");
+        assert_eq!(version, None);
+    }
+
+    #[test]
+    pub fn test_update_by_id_version() {
+        async_std::task::block_on(async {
+            let rb = Rbatis::new();
+            let path = link_temp_db(&rb).await;
+            rb.exec("", "CREATE TABLE biz_activity (id TEXT PRIMARY KEY, name TEXT, version INTEGER)").await.unwrap();
+            rb.exec("", "INSERT INTO biz_activity (id,name,version) VALUES ('12312','test',1)").await.unwrap();
+
+            let activity = BizActivityVersion {
+                id: Some("12312".to_string()),
+                name: Some("test_version".to_string()),
+                version: Some(1),
+            };
+            //UPDATE biz_activity SET name = ?, version = version + 1 WHERE id = ? AND version = ?
+            assert_eq!(rb.update_by_id("", &activity).await.unwrap(), 1);
+            //the version 1 is stale now
+            let r = rb.update_by_id("", &activity).await;
+            assert!(matches!(r, Err(Error::StaleUpdate(_))));
+            let r: BizActivityVersion = rb.fetch_by_id("", &"12312".to_string()).await.unwrap();
+            assert_eq!(r.version, Some(2));
+            drop(rb);
+            remove_db(&path);
+        });
+    }
 }
\ No newline at end of file
@@ -0,0 +1,96 @@
+use chrono::{Local, Timelike};
+use serde_json::{Map, Value};
+
+use rbatis_core::db::{BindArg, DriverType};
+use rbatis_core::Error;
+
+/// the action of fill
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FillAction {
+    Insert,
+    Update,
+}
+
+/// Field Fill Plugin trait,fill the column value before CRUD insert/update
+/// for example: create_time,update_time,created_by(the current user can get from your task_local!/thread_local!)
+pub trait FieldFill: Send + Sync {
+    /// fill the map<column,value> of table,the table_columns is all the columns of table(the map may not have the null column)
+    fn do_fill(&self, driver_type: &DriverType, table_name: &str, table_columns: &[String], action: FillAction, map: &mut Map<String, Value>) -> Result<(), rbatis_core::Error>;
+}
+
+/// fill the time column with local time(2020-02-09 00:00:00),bind as timestamp(see BindArg::timestamp())
+/// insert: fill create_column and update_column if it is null(or not in the map)
+/// update: always fill update_column
+/// the column which not in table_columns is ignored
+pub struct RbatisTimeFillPlugin {
+    pub create_column: String,
+    pub update_column: String,
+}
+
+impl RbatisTimeFillPlugin {
+    pub fn new(create_column: &str, update_column: &str) -> Self {
+        Self {
+            create_column: create_column.to_string(),
+            update_column: update_column.to_string(),
+        }
+    }
+
+    fn now(&self) -> Value {
+        let now = Local::now().naive_local();
+        BindArg::timestamp(&now.with_nanosecond(0).unwrap_or(now))
+    }
+}
+
+impl Default for RbatisTimeFillPlugin {
+    fn default() -> Self {
+        Self::new("create_time", "update_time")
+    }
+}
+
+impl FieldFill for RbatisTimeFillPlugin {
+    fn do_fill(&self, driver_type: &DriverType, table_name: &str, table_columns: &[String], action: FillAction, map: &mut Map<String, Value>) -> Result<(), Error> {
+        match action {
+            FillAction::Insert => {
+                for column in vec![&self.create_column, &self.update_column] {
+                    if table_columns.contains(column) && map.get(column).unwrap_or(&Value::Null).is_null() {
+                        map.insert(column.to_string(), self.now());
+                    }
+                }
+            }
+            FillAction::Update => {
+                if table_columns.contains(&self.update_column) {
+                    map.insert(self.update_column.to_string(), self.now());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_time_fill() {
+        let plugin = RbatisTimeFillPlugin::default();
+        let columns = vec!["id".to_string(), "name".to_string(), "create_time".to_string(), "update_time".to_string()];
+        let mut map = json!({"id": 1, "create_time": null, "update_time": "2020-02-09 00:00:00"}).as_object().unwrap().to_owned();
+        plugin.do_fill(&DriverType::Mysql, "biz_activity", &columns, FillAction::Insert, &mut map).unwrap();
+        assert!(map.get("create_time").unwrap().is_object());
+        assert_eq!(map.get("update_time").unwrap(), "2020-02-09 00:00:00");
+        assert!(!map.contains_key("name"));
+
+        plugin.do_fill(&DriverType::Mysql, "biz_activity", &columns, FillAction::Update, &mut map).unwrap();
+        assert_ne!(map.get("update_time").unwrap(), "2020-02-09 00:00:00");
+
+        //the null field not in map(skip_serializing_if),but the table have the column
+        let mut map = json!({"id": 1}).as_object().unwrap().to_owned();
+        plugin.do_fill(&DriverType::Postgres, "biz_activity", &columns, FillAction::Insert, &mut map).unwrap();
+        assert!(map.contains_key("create_time") && map.contains_key("update_time"));
+        //the table not have the column
+        let mut map = json!({"id": 1}).as_object().unwrap().to_owned();
+        plugin.do_fill(&DriverType::Postgres, "biz_activity", &columns[..2], FillAction::Update, &mut map).unwrap();
+        assert!(!map.contains_key("update_time"));
+    }
+}
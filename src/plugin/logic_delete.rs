@@ -13,8 +13,10 @@ pub trait LogicDelete: Send + Sync {
     fn un_deleted(&self) -> i32;
     /// create_remove_sql
     fn create_remove_sql(&self, driver_type: &DriverType, table_name: &str, table_fields: &str, sql_where: &str) -> Result<String, rbatis_core::Error>;
-    /// create_select_sql
-    fn create_select_sql(&self, driver_type: &DriverType, table_name: &str, table_fields: &str, sql_where: &str) -> Result<String, rbatis_core::Error>;
+    /// create_select_sql,the table_fields is used to check the logic column,select the columns
+    /// join_sql is the joins after table_name(for example: " LEFT JOIN biz_order o ON o.user_id = biz_user.id"),
+    /// the logic column will be "table_name.column" if join_sql not empty
+    fn create_select_sql(&self, driver_type: &DriverType, table_name: &str, join_sql: &str, table_fields: &str, columns: &str, sql_where: &str) -> Result<String, rbatis_core::Error>;
 }
 
 pub enum LogicAction {
@@ -75,24 +77,29 @@ impl LogicDelete for RbatisLogicDeletePlugin {
         };
     }
 
-    fn create_select_sql(&self, driver_type: &DriverType, table_name: &str, table_fields: &str, where_sql: &str) -> Result<String, Error> {
+    fn create_select_sql(&self, driver_type: &DriverType, table_name: &str, join_sql: &str, table_fields: &str, columns: &str, where_sql: &str) -> Result<String, Error> {
         let mut where_sql = where_sql.trim().to_string();
         let mut sql = String::new();
         if table_fields.contains(self.column()) {
+            let column = if join_sql.is_empty() {
+                self.column().to_string()
+            } else {
+                format!("{}.{}", table_name, self.column())
+            };
             if where_sql.is_empty() {
-                where_sql = format!("{} = {}", self.column(), self.un_deleted());
+                where_sql = format!("{} = {}", column, self.un_deleted());
             } else {
                 if where_sql.starts_with("ORDER BY") || where_sql.starts_with("GROUP BY") {
-                    where_sql = format!("{} = {} ", self.column(), self.un_deleted()) + where_sql.as_str();
+                    where_sql = format!("{} = {} ", column, self.un_deleted()) + where_sql.as_str();
                 } else {
-                    where_sql = format!("{} = {} AND ", self.column(), self.un_deleted()) + where_sql.as_str();
+                    where_sql = format!("{} = {} AND ", column, self.un_deleted()) + where_sql.as_str();
                 }
             }
         }
         if !where_sql.is_empty() {
-            sql = format!("SELECT {} FROM {} WHERE {}", table_fields, table_name, where_sql);
+            sql = format!("SELECT {} FROM {}{} WHERE {}", columns, table_name, join_sql, where_sql);
         } else {
-            sql = format!("SELECT {} FROM {}", table_fields, table_name);
+            sql = format!("SELECT {} FROM {}{}", columns, table_name, join_sql);
         }
         Ok(sql)
     }
@@ -123,5 +130,12 @@ mod tests {
         let sql_where = "";
         let result = r.create_remove_sql(&DriverType::Mysql, "test", &table_fields, sql_where);
         assert!(result.is_err());
+
+        let table_fields = "name,age,del";
+        let result = r.create_select_sql(&DriverType::Mysql, "test", "", table_fields, "name", "name = ?").unwrap();
+        assert_eq!("SELECT name FROM test WHERE del = 0 AND name = ?", &result);
+        let join_sql = " LEFT JOIN test_order o ON o.test_id = test.id";
+        let result = r.create_select_sql(&DriverType::Mysql, "test", join_sql, table_fields, "test.*,o.amount", "o.status = ?").unwrap();
+        assert_eq!("SELECT test.*,o.amount FROM test LEFT JOIN test_order o ON o.test_id = test.id WHERE test.del = 0 AND o.status = ?", &result);
     }
 }
\ No newline at end of file
@@ -1,3 +1,4 @@
 pub mod page;
 pub mod logic_delete;
-pub mod intercept;
\ No newline at end of file
+pub mod intercept;
+pub mod fill;
\ No newline at end of file
@@ -1,7 +1,11 @@
 use std::cell::Cell;
 use std::collections::HashMap;
+use std::future::Future;
+use std::ops::{Deref, DerefMut};
 
 use dashmap::DashMap;
+use dashmap::mapref::entry::Entry;
+use futures_core::stream::BoxStream;
 use log::{error, info, LevelFilter, warn};
 use once_cell::sync::OnceCell;
 use serde::de::DeserializeOwned;
@@ -27,11 +31,13 @@ use crate::ast::node::node_type::NodeType;
 use crate::ast::node::select_node::SelectNode;
 use crate::ast::node::update_node::UpdateNode;
 use crate::engine::runtime::RbatisEngine;
+use crate::plugin::fill::FieldFill;
 use crate::plugin::intercept::SqlIntercept;
 use crate::plugin::logic_delete::{LogicDelete, RbatisLogicDeletePlugin};
 use crate::plugin::page::{IPage, IPageRequest, Page, PagePlugin, RbatisPagePlugin};
 use crate::sql::PageLimit;
 use crate::utils::error_util::ToResult;
+use crate::tx::TxGuard;
 use crate::wrapper::Wrapper;
 
 /// rbatis engine
@@ -50,6 +56,8 @@ pub struct Rbatis {
     pub sql_intercepts: Vec<Box<dyn SqlIntercept>>,
     // logic delete plugin
     pub logic_plugin: Option<Box<dyn LogicDelete>>,
+    // field fill plugin
+    pub fill_plugin: Option<Box<dyn FieldFill>>,
 }
 
 impl<'r> Default for Rbatis {
@@ -58,6 +66,44 @@ impl<'r> Default for Rbatis {
     }
 }
 
+/// the tx which taken out of tx_context,it will put back to tx_context when drop
+struct TakenTx<'a> {
+    tx_id: String,
+    tx: Option<DBTx>,
+    tx_context: &'a DashMap<String, DBTx>,
+}
+
+impl<'a> Deref for TakenTx<'a> {
+    type Target = DBTx;
+
+    fn deref(&self) -> &Self::Target {
+        self.tx.as_ref().unwrap()
+    }
+}
+
+impl<'a> DerefMut for TakenTx<'a> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.tx.as_mut().unwrap()
+    }
+}
+
+impl<'a> Drop for TakenTx<'a> {
+    fn drop(&mut self) {
+        if let Some(tx) = self.tx.take() {
+            //an other tx begin with the same tx_id while streaming,not overwrite it
+            match self.tx_context.entry(self.tx_id.clone()) {
+                Entry::Occupied(_) => {
+                    warn!("[rbatis] [{}] tx_id is used by an other tx when the stream drop,rollback the stream tx", self.tx_id);
+                    let _ = tx.spawn_rollback();
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert(tx);
+                }
+            }
+        }
+    }
+}
+
 impl Rbatis {
     pub fn new() -> Self {
         return Self {
@@ -68,6 +114,7 @@ impl Rbatis {
             page_plugin: Box::new(RbatisPagePlugin {}),
             sql_intercepts: vec![],
             logic_plugin: None,
+            fill_plugin: None,
         };
     }
 
@@ -141,7 +188,7 @@ impl Rbatis {
         }
         let conn = self.get_pool()?.begin().await?;
         //send tx to context
-        self.tx_context.insert(new_tx_id.to_string(), conn);
+        self.put_tx(new_tx_id, conn)?;
         info!("[rbatis] [{}] Begin", new_tx_id);
         return Ok(1);
     }
@@ -153,11 +200,27 @@ impl Rbatis {
         }
         let conn = db_conn.begin().await?;
         //send tx to context
-        self.tx_context.insert(new_tx_id.to_string(), conn);
+        self.put_tx(new_tx_id, conn)?;
         info!("[rbatis] [{}] Begin", new_tx_id);
         return Ok(1);
     }
 
+    /// put the begun tx to tx_context,the tx_id may be used by an other task while the conn acquiring,
+    /// if so rollback the tx and return error
+    fn put_tx(&self, tx_id: &str, tx: DBTx) -> Result<(), rbatis_core::Error> {
+        match self.tx_context.entry(tx_id.to_string()) {
+            Entry::Occupied(_) => {
+                //not wait the rollback done
+                let _ = tx.spawn_rollback();
+                Err(rbatis_core::Error::from(format!("[rbatis] tx:{} already exist！please use begin_savepoint() for nested tx", tx_id)))
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(tx);
+                Ok(())
+            }
+        }
+    }
+
     /// commit tx,and return conn
     pub async fn commit(&self, tx_id: &str) -> Result<DBPoolConn, rbatis_core::Error> {
         let tx = self.tx_context.remove(tx_id);
@@ -182,6 +245,85 @@ impl Rbatis {
         return Ok(result);
     }
 
+    /// begin tx with an new tx_id,and return the guard of it
+    /// the tx will be removed and rollback if the guard drop before commit()
+    /// for Example:
+    /// let tx = rb.begin_tx().await?;
+    /// rb.exec(&tx.tx_id, "UPDATE biz_activity SET status = 1").await?;
+    /// tx.commit().await?;
+    pub async fn begin_tx(&self) -> Result<TxGuard<'_>, rbatis_core::Error> {
+        let tx_id = format!("tx_{}", uuid::Uuid::new_v4());
+        self.begin(&tx_id).await?;
+        return Ok(TxGuard::new(self, tx_id));
+    }
+
+    /// run f in an new tx,commit if f return Ok,rollback if f return Err(or panic)
+    /// for Example:
+    /// let r: u64 = rb.transaction(|tx_id| async move {
+    ///     rb.exec(&tx_id, "UPDATE biz_activity SET status = 1").await
+    /// }).await?;
+    pub async fn transaction<F, Fut, T>(&self, f: F) -> Result<T, rbatis_core::Error>
+        where F: FnOnce(String) -> Fut,
+              Fut: Future<Output=Result<T, rbatis_core::Error>> {
+        let tx = self.begin_tx().await?;
+        match f(tx.tx_id.clone()).await {
+            Ok(v) => {
+                tx.commit().await?;
+                return Ok(v);
+            }
+            Err(e) => {
+                let tx_id = tx.tx_id.clone();
+                if let Err(rollback_err) = tx.rollback().await {
+                    error!("[rbatis] [{}] Rollback fail:{}", tx_id, rollback_err);
+                }
+                return Err(e);
+            }
+        }
+    }
+
+    /// begin an savepoint(nested tx) in the exist tx,return the savepoint depth
+    /// for Example:
+    /// rb.begin("1").await?;
+    /// rb.begin_savepoint("1").await?;
+    /// //the sql after begin_savepoint can be rollback by rollback_to_savepoint,the outer tx is not changed
+    /// rb.rollback_to_savepoint("1").await?;
+    /// rb.commit("1").await?;
+    pub async fn begin_savepoint(&self, tx_id: &str) -> Result<u32, rbatis_core::Error> {
+        let tx = self.tx_context.get_mut(tx_id);
+        if tx.is_none() {
+            return Err(rbatis_core::Error::from(format!("[rbatis] tx:{} not exist！", tx_id)));
+        }
+        let depth = tx.unwrap().savepoint().await?;
+        info!("[rbatis] [{}] Begin Savepoint {}", tx_id, depth);
+        return Ok(depth);
+    }
+
+    /// release the last savepoint,the changes is keep in the outer tx
+    pub async fn release_savepoint(&self, tx_id: &str) -> Result<u32, rbatis_core::Error> {
+        let tx = self.tx_context.get_mut(tx_id);
+        if tx.is_none() {
+            return Err(rbatis_core::Error::from(format!("[rbatis] tx:{} not exist！", tx_id)));
+        }
+        let mut tx = tx.unwrap();
+        let depth = tx.savepoint_depth;
+        tx.release_savepoint().await?;
+        info!("[rbatis] [{}] Release Savepoint {}", tx_id, depth);
+        return Ok(depth - 1);
+    }
+
+    /// rollback to the last savepoint,only the changes after begin_savepoint is rollback
+    pub async fn rollback_to_savepoint(&self, tx_id: &str) -> Result<u32, rbatis_core::Error> {
+        let tx = self.tx_context.get_mut(tx_id);
+        if tx.is_none() {
+            return Err(rbatis_core::Error::from(format!("[rbatis] tx:{} not exist！", tx_id)));
+        }
+        let mut tx = tx.unwrap();
+        let depth = tx.savepoint_depth;
+        tx.rollback_to_savepoint().await?;
+        info!("[rbatis] [{}] Rollback Savepoint {}", tx_id, depth);
+        return Ok(depth - 1);
+    }
+
 
     /// fetch result(row sql)
     pub async fn fetch<T>(&self, tx_id: &str, sql: &str) -> Result<T, rbatis_core::Error>
@@ -294,6 +436,85 @@ impl Rbatis {
         return Ok(result);
     }
 
+    /// fetch rows(prepare sql),every row decode into T directly,without the serde_json::Value object.
+    /// u64,decimal string... will not lose precision in the json round trip
+    ///
+    /// for Example:
+    /// let arr: Vec<BizActivity> = rb.fetch_prepare_decode("", "select * from biz_activity", &vec![]).await?;
+    /// let counts: Vec<u64> = rb.fetch_prepare_decode("", "select count(1) from biz_activity", &vec![]).await?;
+    pub async fn fetch_prepare_decode<T>(&self, tx_id: &str, sql: &str, args: &Vec<serde_json::Value>) -> Result<Vec<T>, rbatis_core::Error>
+        where T: DeserializeOwned + Send {
+
+        //sql intercept
+        let mut sql = sql.to_string();
+        let mut args = args.clone();
+        for item in &self.sql_intercepts {
+            item.do_intercept(self, &mut sql, &mut args, true);
+        }
+
+        info!("[rbatis] [{}] Query ==> {}", tx_id, &sql);
+        info!("[rbatis] [{}] Args  ==> {}", tx_id, serde_json::to_string(&args).unwrap_or("".to_string()));
+        let result: Vec<T>;
+        if tx_id.is_empty() {
+            let mut conn = self.get_pool()?.acquire().await?;
+            let q: DBQuery = self.bind_arg(&sql, &args)?;
+            let mut c = conn.fetch_parperd(q)?;
+            result = c.fetch_decode().await?;
+        } else {
+            let q: DBQuery = self.bind_arg(&sql, &args)?;
+            let conn = self.tx_context.get_mut(tx_id);
+            if conn.is_none() {
+                return Err(rbatis_core::Error::from(format!("[rbatis] tx:{} not exist！", tx_id)));
+            }
+            let mut conn = conn.unwrap();
+            let mut c = conn.fetch_parperd(q)?;
+            result = c.fetch_decode().await?;
+        }
+        info!("[rbatis] [{}] ReturnRows <== {}", tx_id, result.len());
+        return Ok(result);
+    }
+
+    /// fetch result as stream(prepare sql),the rows will decode into T one by one,not collect into Vec.
+    /// if tx_id not empty,the tx is taken out of tx_context until the stream drop(use the tx_id before it return 'not exist' error)
+    /// if an other tx begin with the same tx_id while streaming,the stream tx will be rollback(not put back) when the stream drop
+    ///
+    /// for Example:
+    /// use async_std::stream::StreamExt;
+    /// let mut stream = rb.fetch_prepare_stream::<BizActivity>("", "select * from biz_activity", &vec![]).await?;
+    /// while let Some(item) = stream.next().await {
+    ///     let item = item?;
+    /// }
+    pub async fn fetch_prepare_stream<'a, T>(&'a self, tx_id: &str, sql: &str, args: &Vec<serde_json::Value>) -> Result<BoxStream<'a, Result<T, rbatis_core::Error>>, rbatis_core::Error>
+        where T: DeserializeOwned + Send + 'a {
+
+        //sql intercept
+        let mut sql = sql.to_string();
+        let mut args = args.clone();
+        for item in &self.sql_intercepts {
+            item.do_intercept(self, &mut sql, &mut args, true);
+        }
+
+        info!("[rbatis] [{}] Query(stream) ==> {}", tx_id, &sql);
+        info!("[rbatis] [{}] Args  ==> {}", tx_id, serde_json::to_string(&args).unwrap_or("".to_string()));
+        if tx_id.is_empty() {
+            let conn = self.get_pool()?.acquire().await?;
+            return Ok(conn.fetch_stream(sql, args));
+        } else {
+            //take the tx out of tx_context(not hold the dashmap lock),put it back when the stream drop
+            let tx = self.tx_context.remove(tx_id);
+            if tx.is_none() {
+                return Err(rbatis_core::Error::from(format!("[rbatis] tx:{} not exist！", tx_id)));
+            }
+            let (tx_id, tx) = tx.unwrap();
+            let tx = TakenTx {
+                tx_id,
+                tx: Some(tx),
+                tx_context: &self.tx_context,
+            };
+            return Ok(DBTx::fetch_stream(tx, sql, args));
+        }
+    }
+
     /// exec sql(prepare sql)
     pub async fn exec_prepare(&self, tx_id: &str, sql: &str, args: &Vec<serde_json::Value>) -> Result<u64, rbatis_core::Error> {
 
@@ -329,6 +550,40 @@ impl Rbatis {
     }
 
 
+    /// exec sql(prepare sql),return (rows_affected,last_insert_id)
+    /// the last_insert_id only support mysql and sqlite,postgres please use 'RETURNING' sql with fetch_prepare()
+    pub async fn exec_prepare_last_id(&self, tx_id: &str, sql: &str, args: &Vec<serde_json::Value>) -> Result<(u64, i64), rbatis_core::Error> {
+
+        //sql intercept
+        let mut sql = sql.to_string();
+        let mut args = args.clone();
+        for item in &self.sql_intercepts {
+            item.do_intercept(self, &mut sql, &mut args, true);
+        }
+
+        info!("[rbatis] [{}] Exec ==> {}", tx_id, &sql);
+        info!("[rbatis] [{}] Args ==> {}", tx_id, serde_json::to_string(&args).unwrap_or("".to_string()));
+        let result;
+        let last_id;
+        if tx_id.is_empty() {
+            let q: DBQuery = self.bind_arg(&sql, &args)?;
+            let mut conn = self.get_pool()?.acquire().await?;
+            result = conn.execute_parperd(q).await?;
+            last_id = conn.last_insert_id()?;
+        } else {
+            let q: DBQuery = self.bind_arg(&sql, &args)?;
+            let conn = self.tx_context.get_mut(tx_id);
+            if conn.is_none() {
+                return Err(rbatis_core::Error::from(format!("[rbatis] tx:{} not exist！", tx_id)));
+            }
+            let mut conn = conn.unwrap();
+            result = conn.execute_parperd(q).await?;
+            last_id = conn.last_insert_id()?;
+        }
+        info!("[rbatis] [{}] RowsAffected <== {}, LastInsertId <== {}", tx_id, result, last_id);
+        return Ok((result, last_id));
+    }
+
     fn py_to_sql(&self, py: &str, arg: &serde_json::Value) -> Result<(String, Vec<serde_json::Value>), rbatis_core::Error> {
         let nodes = Py::parse_and_cache(py)?;
         let mut arg_array = vec![];
@@ -0,0 +1,33 @@
+use rbatis_core::db::DriverType;
+
+use crate::sql::BatchLimit;
+
+impl BatchLimit for DriverType {
+    fn max_bind_params(&self) -> rbatis_core::Result<usize> {
+        return match self {
+            DriverType::Mysql => {
+                //prepared statement placeholders is u16
+                Ok(65535)
+            }
+            DriverType::Postgres => {
+                //bind message parameter count is i16(unsigned used)
+                Ok(65535)
+            }
+            DriverType::Sqlite => {
+                //SQLITE_MAX_VARIABLE_NUMBER default 999 (before 3.32.0)
+                Ok(999)
+            }
+            DriverType::None => {
+                Err(rbatis_core::Error::from(format!("[rbatis] not support now for DriverType:{:?}", DriverType::None)))
+            }
+        };
+    }
+}
+
+#[test]
+pub fn test_max_bind_params() {
+    assert_eq!(DriverType::Mysql.max_bind_params().unwrap(), 65535);
+    assert_eq!(DriverType::Postgres.max_bind_params().unwrap(), 65535);
+    assert_eq!(DriverType::Sqlite.max_bind_params().unwrap(), 999);
+    assert!(DriverType::None.max_bind_params().is_err());
+}
@@ -1,11 +1,56 @@
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 pub mod limit;
 
 pub mod date;
 
+pub mod upsert;
+
+pub mod batch;
+
+pub mod operator;
+
 
 pub trait PageLimit {
     /// return  sql
     fn page_limit_sql(&self, offset: u64, size: u64) -> rbatis_core::Result<String>;
-}
\ No newline at end of file
+}
+
+pub trait UpsertSql {
+    /// return the upsert sql(append to insert sql)
+    /// for example: ON DUPLICATE KEY UPDATE name = VALUES(name)
+    fn upsert_sql(&self, conflict_columns: &[String], update_columns: &[String]) -> rbatis_core::Result<String>;
+}
+
+pub trait BatchLimit {
+    /// return the max bind params count of one sql,batch insert will split by it
+    fn max_bind_params(&self) -> rbatis_core::Result<usize>;
+}
+/// the dialect operator of Wrapper
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum SqlOperator {
+    /// case-insensitive like
+    ILike,
+    NotILike,
+    /// regex match
+    Regex,
+    NotRegex,
+    /// null safe equal
+    IsNotDistinctFrom,
+    IsDistinctFrom,
+}
+
+pub trait OperatorSql {
+    /// return the condition sql of operator,or error if the driver type not support it
+    /// for example: postgres "name ILIKE $1" ,mysql "LOWER(name) LIKE LOWER(?)"
+    fn operator_sql(&self, op: &SqlOperator, column: &str, arg: &str) -> rbatis_core::Result<String>;
+
+    /// return the json field(as text) sql of column
+    /// for example: postgres "data->>'name'" ,mysql "JSON_UNQUOTE(JSON_EXTRACT(data, '$.name'))"
+    fn json_path_sql(&self, column: &str, path: &[String]) -> rbatis_core::Result<String>;
+
+    /// return the sql of json field is null(json null or not exist)
+    /// for example: postgres "data->>'name' IS NULL" ,mysql "COALESCE(JSON_TYPE(JSON_EXTRACT(data, '$.name')), 'NULL') = 'NULL'"
+    fn json_is_null_sql(&self, column: &str, path: &[String]) -> rbatis_core::Result<String>;
+}
@@ -0,0 +1,120 @@
+use rbatis_core::db::DriverType;
+
+use crate::sql::{OperatorSql, SqlOperator};
+
+impl OperatorSql for DriverType {
+    fn operator_sql(&self, op: &SqlOperator, column: &str, arg: &str) -> rbatis_core::Result<String> {
+        return match self {
+            DriverType::Mysql => {
+                match op {
+                    SqlOperator::ILike => Ok(format!("LOWER({}) LIKE LOWER({})", column, arg)),
+                    SqlOperator::NotILike => Ok(format!("LOWER({}) NOT LIKE LOWER({})", column, arg)),
+                    SqlOperator::Regex => Ok(format!("{} REGEXP {}", column, arg)),
+                    SqlOperator::NotRegex => Ok(format!("{} NOT REGEXP {}", column, arg)),
+                    SqlOperator::IsNotDistinctFrom => Ok(format!("{} <=> {}", column, arg)),
+                    SqlOperator::IsDistinctFrom => Ok(format!("NOT ({} <=> {})", column, arg)),
+                }
+            }
+            DriverType::Postgres => {
+                match op {
+                    SqlOperator::ILike => Ok(format!("{} ILIKE {}", column, arg)),
+                    SqlOperator::NotILike => Ok(format!("{} NOT ILIKE {}", column, arg)),
+                    SqlOperator::Regex => Ok(format!("{} ~ {}", column, arg)),
+                    SqlOperator::NotRegex => Ok(format!("{} !~ {}", column, arg)),
+                    SqlOperator::IsNotDistinctFrom => Ok(format!("{} IS NOT DISTINCT FROM {}", column, arg)),
+                    SqlOperator::IsDistinctFrom => Ok(format!("{} IS DISTINCT FROM {}", column, arg)),
+                }
+            }
+            DriverType::Sqlite => {
+                match op {
+                    SqlOperator::ILike => Ok(format!("LOWER({}) LIKE LOWER({})", column, arg)),
+                    SqlOperator::NotILike => Ok(format!("LOWER({}) NOT LIKE LOWER({})", column, arg)),
+                    //sqlite REGEXP need an user function
+                    SqlOperator::Regex | SqlOperator::NotRegex => {
+                        Err(rbatis_core::Error::from(format!("[rbatis] not support operator {:?} for DriverType:{:?}", op, self)))
+                    }
+                    SqlOperator::IsNotDistinctFrom => Ok(format!("{} IS {}", column, arg)),
+                    SqlOperator::IsDistinctFrom => Ok(format!("{} IS NOT {}", column, arg)),
+                }
+            }
+            DriverType::None => {
+                Err(rbatis_core::Error::from(format!("[rbatis] not support now for DriverType:{:?}", DriverType::None)))
+            }
+        };
+    }
+
+    fn json_path_sql(&self, column: &str, path: &[String]) -> rbatis_core::Result<String> {
+        if path.is_empty() {
+            return Err(rbatis_core::Error::from("[rbatis] json path can not be empty!"));
+        }
+        return match self {
+            DriverType::Mysql => {
+                Ok(format!("JSON_UNQUOTE(JSON_EXTRACT({}, '{}'))", column, json_path(path)))
+            }
+            DriverType::Postgres => {
+                if path.len() == 1 && !is_index(&path[0]) {
+                    Ok(format!("{}->>'{}'", column, path[0]))
+                } else {
+                    Ok(format!("{}#>>'{{{}}}'", column, path.join(",")))
+                }
+            }
+            DriverType::Sqlite => {
+                Ok(format!("json_extract({}, '{}')", column, json_path(path)))
+            }
+            DriverType::None => {
+                Err(rbatis_core::Error::from(format!("[rbatis] not support now for DriverType:{:?}", DriverType::None)))
+            }
+        };
+    }
+
+    fn json_is_null_sql(&self, column: &str, path: &[String]) -> rbatis_core::Result<String> {
+        return match self {
+            DriverType::Mysql => {
+                //JSON_UNQUOTE() of json null is 'null',check the json type instead
+                if path.is_empty() {
+                    return Err(rbatis_core::Error::from("[rbatis] json path can not be empty!"));
+                }
+                Ok(format!("COALESCE(JSON_TYPE(JSON_EXTRACT({}, '{}')), 'NULL') = 'NULL'", column, json_path(path)))
+            }
+            _ => Ok(format!("{} IS NULL", self.json_path_sql(column, path)?)),
+        };
+    }
+}
+
+/// ["a","0","b"] => $.a[0].b
+fn json_path(path: &[String]) -> String {
+    let mut s = "$".to_string();
+    for x in path {
+        if is_index(x) {
+            s.push_str(format!("[{}]", x).as_str());
+        } else {
+            s.push_str(format!(".{}", x).as_str());
+        }
+    }
+    s
+}
+
+fn is_index(part: &str) -> bool {
+    !part.is_empty() && part.chars().all(|c| c.is_ascii_digit())
+}
+
+#[test]
+pub fn test_operator_sql() {
+    assert_eq!(DriverType::Postgres.operator_sql(&SqlOperator::ILike, "name", "$1").unwrap(), "name ILIKE $1");
+    assert_eq!(DriverType::Mysql.operator_sql(&SqlOperator::ILike, "name", "?").unwrap(), "LOWER(name) LIKE LOWER(?)");
+    assert_eq!(DriverType::Mysql.operator_sql(&SqlOperator::IsNotDistinctFrom, "name", "?").unwrap(), "name <=> ?");
+    assert_eq!(DriverType::Sqlite.operator_sql(&SqlOperator::IsDistinctFrom, "name", "?").unwrap(), "name IS NOT ?");
+    assert!(DriverType::Sqlite.operator_sql(&SqlOperator::Regex, "name", "?").is_err());
+    assert!(DriverType::None.operator_sql(&SqlOperator::ILike, "name", "?").is_err());
+
+    let path = vec!["a".to_string(), "0".to_string(), "b".to_string()];
+    assert_eq!(DriverType::Postgres.json_path_sql("data", &path[..1]).unwrap(), "data->>'a'");
+    assert_eq!(DriverType::Postgres.json_path_sql("data", &path).unwrap(), "data#>>'{a,0,b}'");
+    assert_eq!(DriverType::Mysql.json_path_sql("data", &path).unwrap(), "JSON_UNQUOTE(JSON_EXTRACT(data, '$.a[0].b'))");
+    assert_eq!(DriverType::Sqlite.json_path_sql("data", &path).unwrap(), "json_extract(data, '$.a[0].b')");
+
+    assert_eq!(DriverType::Mysql.json_is_null_sql("data", &path).unwrap(), "COALESCE(JSON_TYPE(JSON_EXTRACT(data, '$.a[0].b')), 'NULL') = 'NULL'");
+    assert_eq!(DriverType::Postgres.json_is_null_sql("data", &path[..1]).unwrap(), "data->>'a' IS NULL");
+    assert_eq!(DriverType::Sqlite.json_is_null_sql("data", &path).unwrap(), "json_extract(data, '$.a[0].b') IS NULL");
+    assert!(DriverType::None.json_is_null_sql("data", &path).is_err());
+}
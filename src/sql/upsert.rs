@@ -0,0 +1,61 @@
+use rbatis_core::db::DriverType;
+
+use crate::sql::UpsertSql;
+
+impl UpsertSql for DriverType {
+    fn upsert_sql(&self, conflict_columns: &[String], update_columns: &[String]) -> rbatis_core::Result<String> {
+        return match self {
+            DriverType::Mysql => {
+                //mysql use the table's primary/unique key to check conflict
+                let mut sets = String::new();
+                for x in update_columns {
+                    sets.push_str(format!("{} = VALUES({}),", x, x).as_str());
+                }
+                if sets.is_empty() {
+                    //do nothing
+                    for x in conflict_columns {
+                        sets.push_str(format!("{} = {},", x, x).as_str());
+                    }
+                }
+                sets.pop();
+                if sets.is_empty() {
+                    return Err(rbatis_core::Error::from("[rbatis] upsert sql must have conflict columns or update columns!"));
+                }
+                Ok(format!(" ON DUPLICATE KEY UPDATE {}", sets))
+            }
+            DriverType::Postgres | DriverType::Sqlite => {
+                if conflict_columns.is_empty() {
+                    return Err(rbatis_core::Error::from(format!("[rbatis] upsert sql must have conflict columns for DriverType:{:?}", self)));
+                }
+                let mut sets = String::new();
+                for x in update_columns {
+                    sets.push_str(format!("{} = excluded.{},", x, x).as_str());
+                }
+                sets.pop();
+                if sets.is_empty() {
+                    Ok(format!(" ON CONFLICT ({}) DO NOTHING", conflict_columns.join(",")))
+                } else {
+                    Ok(format!(" ON CONFLICT ({}) DO UPDATE SET {}", conflict_columns.join(","), sets))
+                }
+            }
+            DriverType::None => {
+                Err(rbatis_core::Error::from(format!("[rbatis] not support now for DriverType:{:?}", DriverType::None)))
+            }
+        };
+    }
+}
+
+#[test]
+pub fn test_create_upsert() {
+    let conflict_columns = vec!["id".to_string()];
+    let update_columns = vec!["name".to_string(), "status".to_string()];
+    let mysql_upsert = DriverType::Mysql.upsert_sql(&conflict_columns, &update_columns).unwrap();
+    println!("{}", mysql_upsert);
+    assert_eq!(mysql_upsert, " ON DUPLICATE KEY UPDATE name = VALUES(name),status = VALUES(status)");
+    let pg_upsert = DriverType::Postgres.upsert_sql(&conflict_columns, &update_columns).unwrap();
+    println!("{}", pg_upsert);
+    assert_eq!(pg_upsert, " ON CONFLICT (id) DO UPDATE SET name = excluded.name,status = excluded.status");
+    let sqlite_upsert = DriverType::Sqlite.upsert_sql(&conflict_columns, &vec![]).unwrap();
+    println!("{}", sqlite_upsert);
+    assert_eq!(sqlite_upsert, " ON CONFLICT (id) DO NOTHING");
+}
@@ -0,0 +1,268 @@
+use log::warn;
+
+use rbatis_core::db::DBPoolConn;
+use rbatis_core::Error;
+
+use crate::rbatis::Rbatis;
+
+/// the guard of an tx,commit() or rollback() it,
+/// if the guard drop(early return by '?',or panic) before commit,the tx will be removed and rollback
+/// for Example:
+/// let tx = rb.begin_tx().await?;
+/// rb.exec(&tx.tx_id, "UPDATE biz_activity SET status = 1").await?;
+/// tx.commit().await?;
+pub struct TxGuard<'a> {
+    pub tx_id: String,
+    rb: &'a Rbatis,
+    done: bool,
+}
+
+impl<'a> TxGuard<'a> {
+    pub(crate) fn new(rb: &'a Rbatis, tx_id: String) -> Self {
+        Self {
+            tx_id,
+            rb,
+            done: false,
+        }
+    }
+
+    /// commit tx,and return conn
+    pub async fn commit(mut self) -> Result<DBPoolConn, Error> {
+        self.done = true;
+        self.rb.commit(&self.tx_id).await
+    }
+
+    /// rollback tx,and return conn
+    pub async fn rollback(mut self) -> Result<DBPoolConn, Error> {
+        self.done = true;
+        self.rb.rollback(&self.tx_id).await
+    }
+}
+
+impl<'a> Drop for TxGuard<'a> {
+    fn drop(&mut self) {
+        if self.done {
+            return;
+        }
+        //can not await in drop,rollback in an spawned task
+        if let Some((_, tx)) = self.rb.tx_context.remove(&self.tx_id) {
+            warn!("[rbatis] [{}] Rollback by drop", self.tx_id);
+            let _ = tx.spawn_rollback();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    use async_std::stream::StreamExt;
+
+    use rbatis_core::Error;
+
+    use crate::rbatis::Rbatis;
+
+    /// the temp sqlite db file,remove it(and the wal files) on drop
+    struct TempDb {
+        path: PathBuf,
+    }
+
+    impl TempDb {
+        fn new() -> Self {
+            let path = std::env::temp_dir().join(format!("rbatis_tx_{}.db", uuid::Uuid::new_v4()));
+            std::fs::File::create(&path).unwrap();
+            Self { path }
+        }
+
+        fn url(&self) -> String {
+            format!("sqlite://{}", self.path.to_str().unwrap())
+        }
+    }
+
+    impl Drop for TempDb {
+        fn drop(&mut self) {
+            let path = self.path.to_str().unwrap();
+            for file in &[path.to_string(), format!("{}-wal", path), format!("{}-shm", path)] {
+                let _ = std::fs::remove_file(file);
+            }
+        }
+    }
+
+    /// the dropped tx is rollback by an spawned task,poll the count until it equal the expect(or timeout)
+    async fn count(rb: &Rbatis, expect: i64) -> i64 {
+        let mut c = -1;
+        for _ in 0..50 {
+            let v: serde_json::Value = rb.fetch("", "SELECT count(1) AS c FROM t").await.unwrap();
+            c = v[0]["c"].as_i64().unwrap();
+            if c == expect {
+                break;
+            }
+            async_std::task::sleep(std::time::Duration::from_millis(100)).await;
+        }
+        c
+    }
+
+    /// poll insert until the table lock is released by the spawned rollback(or timeout)
+    async fn insert(rb: &Rbatis, id: i64) -> Result<u64, Error> {
+        let mut r = Err(Error::from("timeout"));
+        for _ in 0..50 {
+            r = rb.exec("", &format!("INSERT INTO t (id) VALUES ({})", id)).await;
+            if r.is_ok() {
+                break;
+            }
+            async_std::task::sleep(std::time::Duration::from_millis(100)).await;
+        }
+        r
+    }
+
+    async fn insert_twice(rb: &Rbatis) -> Result<(), Error> {
+        let tx = rb.begin_tx().await?;
+        rb.exec(&tx.tx_id, "INSERT INTO t (id) VALUES (1)").await?;
+        rb.exec(&tx.tx_id, "INSERT INTO t (id) VALUES (1)").await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_transaction() {
+        async_std::task::block_on(async {
+            let db = TempDb::new();
+            let rb = Rbatis::new();
+            rb.link(&db.url()).await.unwrap();
+            rb.exec("", "CREATE TABLE t (id INTEGER PRIMARY KEY)").await.unwrap();
+
+            let r: Result<u64, Error> = rb.transaction(|tx_id| {
+                let rb = &rb;
+                async move {
+                    rb.exec(&tx_id, "INSERT INTO t (id) VALUES (1)").await
+                }
+            }).await;
+            assert_eq!(r.unwrap(), 1);
+            assert_eq!(count(&rb, 1).await, 1);
+
+            let r: Result<u64, Error> = rb.transaction(|tx_id| {
+                let rb = &rb;
+                async move {
+                    rb.exec(&tx_id, "INSERT INTO t (id) VALUES (2)").await?;
+                    rb.exec(&tx_id, "INSERT INTO t (id) VALUES (1)").await
+                }
+            }).await;
+            assert!(r.is_err());
+            assert_eq!(count(&rb, 1).await, 1);
+            assert!(rb.tx_context.is_empty());
+        });
+    }
+
+    #[test]
+    fn test_tx_guard_drop() {
+        async_std::task::block_on(async {
+            let db = TempDb::new();
+            let rb = Rbatis::new();
+            rb.link(&db.url()).await.unwrap();
+            rb.exec("", "CREATE TABLE t (id INTEGER PRIMARY KEY)").await.unwrap();
+
+            //the second insert fail,return by '?'
+            assert!(insert_twice(&rb).await.is_err());
+            assert!(rb.tx_context.is_empty());
+            assert_eq!(count(&rb, 0).await, 0);
+
+            let tx = rb.begin_tx().await.unwrap();
+            rb.exec(&tx.tx_id, "INSERT INTO t (id) VALUES (2)").await.unwrap();
+            tx.commit().await.unwrap();
+            assert!(rb.tx_context.is_empty());
+            assert_eq!(count(&rb, 1).await, 1);
+        });
+    }
+
+    #[test]
+    fn test_savepoint() {
+        async_std::task::block_on(async {
+            let db = TempDb::new();
+            let rb = Rbatis::new();
+            rb.link(&db.url()).await.unwrap();
+            rb.exec("", "CREATE TABLE t (id INTEGER PRIMARY KEY)").await.unwrap();
+            rb.begin("1").await.unwrap();
+            assert!(rb.begin("1").await.is_err());
+            rb.exec("1", "INSERT INTO t (id) VALUES (1)").await.unwrap();
+            assert_eq!(rb.begin_savepoint("1").await.unwrap(), 1);
+            rb.exec("1", "INSERT INTO t (id) VALUES (2)").await.unwrap();
+            assert_eq!(rb.rollback_to_savepoint("1").await.unwrap(), 0);
+            //no savepoint
+            assert!(rb.release_savepoint("1").await.is_err());
+            rb.commit("1").await.unwrap();
+            let ids: serde_json::Value = rb.fetch("", "SELECT id FROM t").await.unwrap();
+            assert_eq!(ids, serde_json::json!([{"id": 1}]));
+        });
+    }
+
+    #[test]
+    fn test_stream_in_tx() {
+        async_std::task::block_on(async {
+            let db = TempDb::new();
+            let rb = Rbatis::new();
+            rb.link(&db.url()).await.unwrap();
+            rb.exec("", "CREATE TABLE t (id INTEGER PRIMARY KEY)").await.unwrap();
+            rb.begin("1").await.unwrap();
+            rb.exec("1", "INSERT INTO t (id) VALUES (1),(2)").await.unwrap();
+            let mut stream = rb.fetch_prepare_stream::<serde_json::Value>("1", "SELECT id FROM t", &vec![]).await.unwrap();
+            assert!(stream.next().await.unwrap().is_ok());
+            //the tx is taken by the stream,not block
+            assert!(rb.exec("1", "INSERT INTO t (id) VALUES (3)").await.is_err());
+            drop(stream);
+            rb.exec("1", "INSERT INTO t (id) VALUES (3)").await.unwrap();
+            rb.commit("1").await.unwrap();
+            assert!(rb.tx_context.is_empty());
+            assert_eq!(count(&rb, 3).await, 3);
+        });
+    }
+
+    #[test]
+    fn test_stream_tx_id_reused() {
+        async_std::task::block_on(async {
+            let db = TempDb::new();
+            let rb = Rbatis::new();
+            rb.link(&db.url()).await.unwrap();
+            rb.exec("", "CREATE TABLE t (id INTEGER PRIMARY KEY)").await.unwrap();
+            rb.begin("1").await.unwrap();
+            rb.exec("1", "INSERT INTO t (id) VALUES (1),(2)").await.unwrap();
+            let mut stream = rb.fetch_prepare_stream::<serde_json::Value>("1", "SELECT id FROM t", &vec![]).await.unwrap();
+            assert!(stream.next().await.unwrap().is_ok());
+            //begin an other tx with the same tx_id while streaming
+            rb.begin("1").await.unwrap();
+            //the stream tx not overwrite it,it is rollback
+            drop(stream);
+            assert_eq!(rb.tx_context.len(), 1);
+            rb.commit("1").await.unwrap();
+            assert_eq!(count(&rb, 0).await, 0);
+            insert(&rb, 1).await.unwrap();
+            assert_eq!(count(&rb, 1).await, 1);
+        });
+    }
+
+    #[test]
+    fn test_tx_guard_panic() {
+        let db = TempDb::new();
+        let rb = Arc::new(Rbatis::new());
+        async_std::task::block_on(async {
+            rb.link(&db.url()).await.unwrap();
+            rb.exec("", "CREATE TABLE t (id INTEGER PRIMARY KEY)").await.unwrap();
+        });
+        let rb_clone = rb.clone();
+        let r = std::thread::spawn(move || {
+            async_std::task::block_on(async {
+                let tx = rb_clone.begin_tx().await.unwrap();
+                rb_clone.exec(&tx.tx_id, "INSERT INTO t (id) VALUES (1)").await.unwrap();
+                panic!("test panic");
+            })
+        }).join();
+        assert!(r.is_err());
+        assert!(rb.tx_context.is_empty());
+        async_std::task::block_on(async {
+            assert_eq!(count(&rb, 0).await, 0);
+            //the row is absent and the lock is released after rollback
+            insert(&rb, 1).await.unwrap();
+            assert_eq!(count(&rb, 1).await, 1);
+        });
+    }
+}
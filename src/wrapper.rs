@@ -8,9 +8,17 @@ use rbatis_core::convert::StmtConvert;
 use rbatis_core::db::DriverType;
 use rbatis_core::Error;
 
+use crate::sql::{OperatorSql, PageLimit, SqlOperator};
+
 /// you can serialize to JSON, and Clone, Debug
 /// use json rpc send this Wrapper to server
 ///
+/// the Wrapper hold the condition nodes,the sql and args is render by check()/render(driver_type),
+/// so an Wrapper can be reused by other driver type,and Debug print the sql with inline values
+///
+/// the columns of eq(),order_by(),group_by()... must be identifiers(or table.column),else check() return error.
+/// for the expressions(count(1),date(create_time)...) use order_by_raw(),group_by_raw(),push_sql() + push_stmt_arg()
+///
 /// for Example:
 ///         let w = Wrapper::new(&DriverType::Mysql)
 ///             .eq("id", 1)
@@ -30,13 +38,57 @@ use rbatis_core::Error;
 ///             .order_by(true, &["id", "name"])
 ///             .check().unwrap();
 ///
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct Wrapper {
     pub driver_type: DriverType,
+    /// the sql render by check(),edit the nodes instead of it
     pub sql: String,
+    /// the args render by check()
     pub args: Vec<serde_json::Value>,
     pub error: Option<Error>,
     pub checked: bool,
+    /// select columns,None is all table fields
+    #[serde(default)]
+    pub select_columns: Option<String>,
+    /// join sql,for example: " LEFT JOIN biz_order o ON o.user_id = biz_user.id"
+    #[serde(default)]
+    pub join_sql: String,
+    #[serde(default)]
+    pub limit: Option<u64>,
+    #[serde(default)]
+    pub offset: Option<u64>,
+    /// quote the columns by driver type,see quote_columns()
+    #[serde(default)]
+    pub quote: bool,
+    /// the allowed columns of strict mode,see strict_columns()
+    #[serde(default)]
+    pub allowed_columns: Option<Vec<String>>,
+    /// the condition nodes,render to sql by driver type
+    #[serde(default)]
+    pub nodes: Vec<SqlNode>,
+}
+
+/// the node of Wrapper
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum SqlNode {
+    /// raw sql
+    Sql(String),
+    /// an arg with placeholder,render to "?" or "$n"
+    Arg(Value),
+    /// an arg without placeholder(the placeholder is in the raw sql)
+    RawArg(Value),
+    /// column,quote it if need
+    Column { name: String, quote: bool },
+    /// nodes of an other Wrapper,the "$n" of raw sql will offset by the args before it
+    Group(Vec<SqlNode>),
+    /// dialect operator(ILIKE,REGEXP...),render by OperatorSql
+    Operator { op: SqlOperator, column: Box<SqlNode>, arg: Box<SqlNode> },
+    /// the json field of column,for example: data->>'name'
+    JsonPath { column: Box<SqlNode>, path: Vec<String> },
+    /// the json field of column is null(sql NULL,json null or not exist),render by OperatorSql
+    JsonIsNull { column: Box<SqlNode>, path: Vec<String> },
+    /// an arg compare with the json field,postgres ->> is text,so bind it as text
+    JsonArg(Value),
 }
 
 impl Wrapper {
@@ -47,17 +99,22 @@ impl Wrapper {
             args: vec![],
             error: None,
             checked: false,
+            select_columns: None,
+            join_sql: String::new(),
+            limit: None,
+            offset: None,
+            quote: false,
+            allowed_columns: None,
+            nodes: vec![],
         }
     }
 
     pub fn from(driver_type: &DriverType, sql: &str, args: &Vec<serde_json::Value>) -> Self {
-        Self {
-            driver_type: driver_type.clone(),
-            sql: sql.to_string(),
-            args: args.clone(),
-            error: None,
-            checked: false,
-        }
+        let mut w = Self::new(driver_type);
+        w.sql = sql.to_string();
+        w.args = args.clone();
+        w.nodes = w.to_nodes();
+        w
     }
 
     //check is done？and return cloned Wrapper
@@ -65,19 +122,32 @@ impl Wrapper {
         if self.error.is_some() {
             return Err(self.error.take().unwrap());
         }
+        //old Wrapper(json) only have sql,args
+        if self.nodes.is_empty() {
+            self.nodes = self.to_nodes();
+        }
         //remove and ,or
         self.trim_and();
         self.trim_or();
-        let clone = Wrapper {
-            driver_type: self.driver_type.clone(),
-            sql: self.sql.clone(),
-            args: self.args.clone(),
-            error: self.error.clone(),
-            checked: true,
-        };
+        let (sql, args) = self.render_sql(&self.driver_type)?;
+        self.sql = sql;
+        self.args = args;
+        let mut clone = self.clone();
+        clone.checked = true;
         return Ok(clone);
     }
 
+    /// render sql,args by the driver type,self is not changed
+    /// for Example:
+    /// let w = Wrapper::new(&DriverType::Mysql).eq("a", 1).eq("b", 2);
+    /// w.render(&DriverType::Mysql).unwrap().sql     // a = ? AND b = ?
+    /// w.render(&DriverType::Postgres).unwrap().sql  // a = $1 AND b = $2
+    pub fn render(&self, driver_type: &DriverType) -> Result<Wrapper, Error> {
+        let mut w = self.clone();
+        w.driver_type = driver_type.clone();
+        w.check()
+    }
+
     /// link left Wrapper to this Wrapper
     /// for Example:
     /// let w = Wrapper::new(&DriverType::Postgres).push_sql("(").eq("a", "1").push_sql(")").check().unwrap();
@@ -85,33 +155,29 @@ impl Wrapper {
     /// .and()
     /// .push_wrapper(&w)
     /// .check().unwrap();
-    /// println!("sql:{:?}", w2.sql.as_str());  // sql:"b = ? AND (a = ?)"
+    /// println!("sql:{:?}", w2.sql.as_str());  // sql:"b = $1 AND (a = $2)"
     /// println!("arg:{:?}", w2.args.clone()); // arg:[String("2"), String("1")]
     ///
     pub fn push_wrapper(&mut self, arg: &Wrapper) -> &mut Self {
-        self.push(&arg.sql, &arg.args)
+        if let Some(e) = &arg.error {
+            self.error = Some(e.clone());
+            return self;
+        }
+        self.nodes.push(SqlNode::Group(arg.to_nodes()));
+        self
     }
 
     /// push sql,args into self
     pub fn push<T>(&mut self, sql: &str, args: &[T]) -> &mut Self
         where T: Serialize {
-        let mut new_sql = sql.to_string();
-        if self.driver_type.eq(&DriverType::Postgres) {
-            for index in 0..args.len() {
-                let str = self.driver_type.stmt_convert(index);
-                new_sql = new_sql.replace(str.as_str(), self.driver_type.stmt_convert(index + args.len()).as_str());
-            }
-        }
-        self.sql.push_str(new_sql.as_str());
-
+        let mut nodes = vec![SqlNode::Sql(sql.to_string())];
         let args = serde_json::to_value(args).unwrap_or(serde_json::Value::Null);
-        if args.is_null() {
-            return self;
-        }
-        let args = args.as_array().unwrap();
-        for x in args {
-            self.args.push(x.to_owned());
+        if let Some(args) = args.as_array() {
+            for x in args {
+                nodes.push(SqlNode::RawArg(x.to_owned()));
+            }
         }
+        self.nodes.push(SqlNode::Group(nodes));
         self
     }
 
@@ -150,13 +216,17 @@ impl Wrapper {
 
 
     pub fn set_sql(&mut self, sql: &str) -> &mut Self {
-        self.sql = sql.replace(" and ", " AND ").replace(" or ", " OR ").replace(" where ", " WHERE ");
+        let sql = sql.replace(" and ", " AND ").replace(" or ", " OR ").replace(" where ", " WHERE ");
+        match self.render_sql(&self.driver_type) {
+            Ok((_, args)) => self.set_raw(sql, args),
+            Err(e) => self.error = Some(e),
+        }
         self
     }
 
     pub fn push_sql(&mut self, sql: &str) -> &mut Self {
         let s = sql.replace(" and ", " AND ").replace(" or ", " OR ").replace(" where ", " WHERE ");
-        self.sql.push_str(s.as_str());
+        self.push_text(s.as_str());
         self
     }
 
@@ -166,40 +236,117 @@ impl Wrapper {
             return self;
         }
         if v.is_array() {
-            self.args = v.as_array().unwrap_or(&vec![]).to_owned();
+            match self.render_sql(&self.driver_type) {
+                Ok((sql, _)) => self.set_raw(sql, v.as_array().unwrap_or(&vec![]).to_owned()),
+                Err(e) => self.error = Some(e),
+            }
         }
         self
     }
 
     pub fn push_arg<T>(&mut self, arg: T) -> &mut Self where T: Serialize {
         let v = serde_json::to_value(arg).unwrap_or(serde_json::Value::Null);
-        self.args.push(v);
+        self.nodes.push(SqlNode::RawArg(v));
+        self
+    }
+
+    /// push an arg and it's placeholder("?" or "$n" by the driver type)
+    /// for Example:
+    /// let w = Wrapper::new(&DriverType::Postgres).push_sql("id = ").push_stmt_arg(1).check().unwrap();
+    /// println!("sql:{:?}", w.sql.as_str());  // sql:"id = $1"
+    pub fn push_stmt_arg<T>(&mut self, arg: T) -> &mut Self where T: Serialize {
+        let v = serde_json::to_value(arg).unwrap_or(serde_json::Value::Null);
+        self.nodes.push(SqlNode::Arg(v));
         self
     }
 
     pub fn pop_arg(&mut self) -> &mut Self {
-        self.args.pop();
+        match self.render_sql(&self.driver_type) {
+            Ok((sql, mut args)) => {
+                args.pop();
+                self.set_raw(sql, args);
+            }
+            Err(e) => self.error = Some(e),
+        }
         self
     }
 
+    /// the sql of the Wrapper is empty
+    pub fn is_empty(&self) -> bool {
+        is_empty_nodes(&self.to_nodes())
+    }
+
+    /// the nodes,or the sql,args of old Wrapper
+    fn to_nodes(&self) -> Vec<SqlNode> {
+        if !self.nodes.is_empty() || (self.sql.is_empty() && self.args.is_empty()) {
+            return self.nodes.clone();
+        }
+        let mut nodes = vec![SqlNode::Sql(self.sql.clone())];
+        for x in &self.args {
+            nodes.push(SqlNode::RawArg(x.clone()));
+        }
+        nodes
+    }
+
+    fn set_raw(&mut self, sql: String, args: Vec<Value>) {
+        self.nodes = vec![SqlNode::Sql(sql)];
+        for x in args {
+            self.nodes.push(SqlNode::RawArg(x));
+        }
+    }
+
+    /// push raw sql,merge into the last sql node
+    fn push_text(&mut self, sql: &str) {
+        if let Some(SqlNode::Sql(last)) = self.nodes.last_mut() {
+            last.push_str(sql);
+            return;
+        }
+        self.nodes.push(SqlNode::Sql(sql.to_string()));
+    }
+
+    fn push_value<T>(&mut self, obj: T) where T: Serialize {
+        let v = serde_json::to_value(obj).unwrap_or(serde_json::Value::Null);
+        self.nodes.push(SqlNode::Arg(v));
+    }
+
+    /// render the nodes to sql,args
+    fn render_sql(&self, driver_type: &DriverType) -> Result<(String, Vec<Value>), Error> {
+        let mut sql = String::new();
+        let mut args = vec![];
+        render_nodes(driver_type, &self.to_nodes(), false, false, &mut sql, &mut args)?;
+        Ok((sql, args))
+    }
+
     fn is_start_opt(&self) -> bool {
-        let sql = self.sql.trim_end();
-        sql.ends_with("WHERE")
-            || sql.ends_with("AND")
-            || sql.ends_with("OR")
-            || sql.ends_with("(")
-            || sql.ends_with(",")
-            || sql.ends_with("=")
-            || sql.ends_with("+")
-            || sql.ends_with("-")
-            || sql.ends_with("*")
-            || sql.ends_with("/")
+        for node in self.nodes.iter().rev() {
+            match node {
+                SqlNode::Sql(sql) => {
+                    let sql = sql.trim_end();
+                    if sql.is_empty() {
+                        continue;
+                    }
+                    return sql.ends_with("WHERE")
+                        || sql.ends_with("AND")
+                        || sql.ends_with("OR")
+                        || sql.ends_with("(")
+                        || sql.ends_with(",")
+                        || sql.ends_with("=")
+                        || sql.ends_with("+")
+                        || sql.ends_with("-")
+                        || sql.ends_with("*")
+                        || sql.ends_with("/");
+                }
+                SqlNode::RawArg(_) => {}
+                _ => return false,
+            }
+        }
+        true
     }
 
     /// link wrapper sql, if end with where , do nothing
     pub fn and(&mut self) -> &mut Self {
         if !self.is_start_opt() {
-            self.sql.push_str(" AND ");
+            self.push_text(" AND ");
         }
         self
     }
@@ -207,14 +354,263 @@ impl Wrapper {
     /// link wrapper sql, if end with where , do nothing
     pub fn or(&mut self) -> &mut Self {
         if !self.is_start_opt() {
-            self.sql.push_str(" OR ");
+            self.push_text(" OR ");
+        }
+        self
+    }
+
+    /// push an nested condition group "( ... )" linked by AND,the group will be ignore if the closure push nothing
+    /// for Example:
+    /// let w = Wrapper::new(&DriverType::Postgres)
+    ///     .eq("a", 1)
+    ///     .and_nested(|w| w.eq("b", 2).or().eq("c", 3))
+    ///     .check().unwrap();
+    /// println!("sql:{:?}", w.sql.as_str());  // sql:"a = $1 AND (b = $2 OR c = $3)"
+    pub fn and_nested<F>(&mut self, f: F) -> &mut Self
+        where F: FnOnce(&mut Wrapper) -> &mut Wrapper {
+        self.push_nested(false, f)
+    }
+
+    /// push an nested condition group "( ... )" linked by OR,the group will be ignore if the closure push nothing
+    /// for Example:
+    /// let w = Wrapper::new(&DriverType::Mysql)
+    ///     .eq("a", 1)
+    ///     .or_nested(|w| w.eq("b", 2).eq("c", 3))
+    ///     .check().unwrap();
+    /// println!("sql:{:?}", w.sql.as_str());  // sql:"a = ? OR (b = ? AND c = ?)"
+    pub fn or_nested<F>(&mut self, f: F) -> &mut Self
+        where F: FnOnce(&mut Wrapper) -> &mut Wrapper {
+        self.push_nested(true, f)
+    }
+
+    fn push_nested<F>(&mut self, is_or: bool, f: F) -> &mut Self
+        where F: FnOnce(&mut Wrapper) -> &mut Wrapper {
+        let mut nested = Wrapper::new(&self.driver_type);
+        nested.quote = self.quote;
+        nested.allowed_columns = self.allowed_columns.clone();
+        f(&mut nested);
+        if let Some(e) = nested.error.take() {
+            self.error = Some(e);
+            return self;
+        }
+        nested.trim_and();
+        nested.trim_or();
+        if nested.is_empty() {
+            return self;
+        }
+        if is_or {
+            self.or();
+        } else {
+            self.and();
+        }
+        self.push_text("(");
+        self.nodes.push(SqlNode::Group(nested.nodes));
+        self.push_text(")");
+        self
+    }
+
+    /// quote the columns of the methods after it(eq,order_by...),mysql: `name` ,postgres/sqlite: "name"
+    /// for Example:
+    /// let w = Wrapper::new(&DriverType::Mysql).quote_columns().eq("name", "a").check().unwrap();
+    /// println!("sql:{:?}", w.sql.as_str());  // sql:"`name` = ?"
+    pub fn quote_columns(&mut self) -> &mut Self {
+        self.quote = true;
+        self
+    }
+
+    /// strict mode: the columns of the methods after it must in table_fields,else check() return error
+    /// for Example:
+    /// let sort = "name";//from request
+    /// let w = Wrapper::new(&DriverType::Mysql).strict_columns(&BizActivity::table_fields()).order_by(true, &[sort]).check()?;
+    pub fn strict_columns(&mut self, table_fields: &str) -> &mut Self {
+        let mut columns = vec![];
+        for x in table_fields.split(",") {
+            //"user_name AS name" => user_name
+            let column = x.split(" AS ").next().unwrap_or_default().trim();
+            if !column.is_empty() {
+                columns.push(column.to_string());
+            }
+        }
+        self.allowed_columns = Some(columns);
+        self
+    }
+
+    /// check the column is an identifier(or table.column),the quote is render by driver type
+    fn make_column(&mut self, column: &str) -> SqlNode {
+        let column = column.trim();
+        let node = SqlNode::Column { name: column.to_string(), quote: self.quote };
+        let parts: Vec<&str> = column.split(".").collect();
+        for part in &parts {
+            if !is_identifier(part) {
+                self.error = Some(Error::from(format!("[rbatis] wrapper invalid column: {}", column)));
+                return node;
+            }
+        }
+        if let Some(allowed) = &self.allowed_columns {
+            let name = unquote(parts[parts.len() - 1]);
+            if !allowed.iter().any(|x| x.eq(name)) {
+                self.error = Some(Error::from(format!("[rbatis] wrapper unknown column: {}", column)));
+            }
+        }
+        node
+    }
+
+    /// the column sql checked by the rules of this wrapper(quote_columns,strict_columns),for example the column of sum(column)
+    pub fn column_sql(&self, driver_type: &DriverType, column: &str) -> Result<String, Error> {
+        let mut w = Wrapper::new(driver_type);
+        w.quote = self.quote;
+        w.allowed_columns = self.allowed_columns.clone();
+        w.push_column(column);
+        Ok(w.check()?.sql)
+    }
+
+    fn push_column(&mut self, column: &str) {
+        let node = self.make_column(column);
+        self.nodes.push(node);
+    }
+
+    /// sql: column op arg,the sql is create by the driver type
+    fn push_operator<T>(&mut self, column: SqlNode, op: SqlOperator, obj: T) -> &mut Self
+        where T: Serialize {
+        self.and();
+        let v = serde_json::to_value(obj).unwrap_or(serde_json::Value::Null);
+        self.nodes.push(SqlNode::Operator { op, column: Box::new(column), arg: Box::new(SqlNode::Arg(v)) });
+        self
+    }
+
+    /// "a.0.b" => ["a","0","b"],the part must be an identifier
+    fn make_json_path(&mut self, column: &str, path: &str) -> SqlNode {
+        let column = self.make_column(column);
+        let path: Vec<String> = path.split(".").map(|x| x.trim().to_string()).collect();
+        if !path.iter().all(|x| is_identifier(x) && unquote(x).len() == x.len()) {
+            self.error = Some(Error::from(format!("[rbatis] wrapper invalid json path: {}", path.join("."))));
         }
+        SqlNode::JsonPath { column: Box::new(column), path }
+    }
+
+    /// sql: column op arg
+    fn push_compare<T>(&mut self, column: &str, op: &str, obj: T) -> &mut Self
+        where T: Serialize {
+        self.and();
+        self.push_column(column);
+        self.push_text(op);
+        self.push_value(obj);
         self
     }
 
+    /// trim the " WHERE "," AND "," OR " at the end,before ORDER BY,GROUP BY
+    fn trim_end_opt(&mut self) {
+        if let Some(SqlNode::Sql(last)) = self.nodes.last_mut() {
+            *last = last.trim_end_matches(" WHERE ")
+                .trim_end_matches(" AND ")
+                .trim_end_matches(" OR ").to_string();
+        }
+    }
+
+    /// select columns instead of all table fields(CRUD *_by_wrapper methods)
+    /// the column must be an identifier(or table.column,table.*),checked by the rules of this wrapper(quote_columns,strict_columns)
+    /// for Example:
+    /// let w = Wrapper::new(&DriverType::Mysql).select(&["id", "name"]).eq("status", 1).check().unwrap();
+    /// rb.list_by_wrapper::<BizActivity>("", &w) // SELECT id,name FROM biz_activity WHERE status = ?
+    pub fn select<C>(&mut self, columns: &[C]) -> &mut Self
+        where C: AsRef<str> {
+        if columns.is_empty() {
+            self.error = Some(Error::from("[rbatis] wrapper select columns can not be empty!"));
+            return self;
+        }
+        let mut sqls = vec![];
+        for column in columns {
+            let column = column.as_ref().trim();
+            if let Some(table) = column.strip_suffix(".*") {
+                //table.*
+                if !self.check_identifier(table, "column") {
+                    return self;
+                }
+                sqls.push(format!("{}.*", quote_column(&self.driver_type, table, self.quote)));
+            } else if let SqlNode::Column { name, quote } = self.make_column(column) {
+                sqls.push(quote_column(&self.driver_type, &name, quote));
+            }
+        }
+        if self.error.is_none() {
+            self.select_columns = Some(sqls.join(","));
+        }
+        self
+    }
+
+    /// sql:   LEFT JOIN table ON left_column = right_column
+    /// the table can have an alias("biz_order o"),the columns must be identifiers(or table.column)
+    /// for Example:
+    /// let w = Wrapper::new(&DriverType::Mysql)
+    ///     .select(&["biz_user.*", "o.amount"])
+    ///     .left_join("biz_order o", "o.user_id", "biz_user.id")
+    ///     .eq("o.status", 1)
+    ///     .check().unwrap();
+    /// rb.list_by_wrapper::<UserOrder>("", &w) // SELECT biz_user.*,o.amount FROM biz_user LEFT JOIN biz_order o ON o.user_id = biz_user.id WHERE o.status = ?
+    pub fn left_join(&mut self, table: &str, left_column: &str, right_column: &str) -> &mut Self {
+        self.push_join("LEFT JOIN", table, left_column, right_column)
+    }
+
+    /// sql:   INNER JOIN table ON left_column = right_column
+    pub fn inner_join(&mut self, table: &str, left_column: &str, right_column: &str) -> &mut Self {
+        self.push_join("INNER JOIN", table, left_column, right_column)
+    }
+
+    fn push_join(&mut self, join: &str, table: &str, left_column: &str, right_column: &str) -> &mut Self {
+        //table or "table alias"
+        let names: Vec<&str> = table.split_whitespace().collect();
+        if names.is_empty() || names.len() > 2 {
+            self.error = Some(Error::from(format!("[rbatis] wrapper invalid join table: {}", table)));
+            return self;
+        }
+        for name in &names {
+            if !self.check_identifier(name, "join table") {
+                return self;
+            }
+        }
+        if !self.check_identifier(left_column, "join column") || !self.check_identifier(right_column, "join column") {
+            return self;
+        }
+        let table: Vec<String> = names.iter().map(|x| quote_column(&self.driver_type, x, self.quote)).collect();
+        self.join_sql.push_str(format!(" {} {} ON {} = {}", join, table.join(" "),
+                                       quote_column(&self.driver_type, left_column.trim(), self.quote),
+                                       quote_column(&self.driver_type, right_column.trim(), self.quote)).as_str());
+        self
+    }
+
+    /// the name must be an identifier(or a.b),else set the error
+    fn check_identifier(&mut self, name: &str, kind: &str) -> bool {
+        if name.trim().split(".").all(is_identifier) {
+            return true;
+        }
+        self.error = Some(Error::from(format!("[rbatis] wrapper invalid {}: {}", kind, name)));
+        false
+    }
+
+    /// limit rows of CRUD *_by_wrapper methods,the limit sql is create by the driver type
+    pub fn limit(&mut self, limit: u64) -> &mut Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// skip rows of CRUD *_by_wrapper methods
+    pub fn offset(&mut self, offset: u64) -> &mut Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    /// the limit sql of limit(),offset(),return empty if not set
+    /// for example: mysql " LIMIT 20,10" ,postgres " LIMIT 10 OFFSET 20"
+    pub fn limit_sql(&self) -> Result<String, Error> {
+        if self.limit.is_none() && self.offset.is_none() {
+            return Ok(String::new());
+        }
+        //only offset,the limit is the max value of BIGINT
+        self.driver_type.page_limit_sql(self.offset.unwrap_or(0), self.limit.unwrap_or(i64::MAX as u64))
+    }
+
     pub fn having(&mut self, sql_having: &str) -> &mut Self {
         self.and();
-        self.sql.push_str(format!(" HAVING {} ", sql_having).as_str());
+        self.push_text(format!(" HAVING {} ", sql_having).as_str());
         self
     }
 
@@ -240,7 +636,7 @@ impl Wrapper {
         for (k, v) in map {
             self.eq(k.as_str(), v);
             if (index + 1) != len {
-                self.sql.push_str(" , ");
+                self.push_text(" , ");
                 index += 1;
             }
         }
@@ -250,250 +646,746 @@ impl Wrapper {
     /// equal
     /// for example:
     ///  eq("a",1) " a = 1 "
-    pub fn eq<T>(&mut self, column: &str, obj: T) -> &mut Self
-        where T: Serialize {
-        self.and();
-        let v = serde_json::to_value(obj).unwrap_or(serde_json::Value::Null);
-        self.sql.push_str(column);
-        self.sql.push_str(format!(" = {}", self.driver_type.stmt_convert(self.args.len())).as_str());
-        self.args.push(v);
-
-
-        self
+    pub fn eq<C, T>(&mut self, column: C, obj: T) -> &mut Self
+        where C: AsRef<str>, T: Serialize {
+        self.push_compare(column.as_ref(), " = ", obj)
     }
 
     /// not equal
-    pub fn ne<T>(&mut self, column: &str, obj: T) -> &mut Self
-        where T: Serialize {
-        self.and();
-        let v = serde_json::to_value(obj).unwrap_or(serde_json::Value::Null);
-        self.sql.push_str(column);
-        self.sql.push_str(format!(" <> {}", self.driver_type.stmt_convert(self.args.len())).as_str());
-        self.args.push(v);
-        self
+    pub fn ne<C, T>(&mut self, column: C, obj: T) -> &mut Self
+        where C: AsRef<str>, T: Serialize {
+        self.push_compare(column.as_ref(), " <> ", obj)
     }
 
-    pub fn order_by(&mut self, is_asc: bool, columns: &[&str]) -> &mut Self {
+    pub fn order_by<C>(&mut self, is_asc: bool, columns: &[C]) -> &mut Self
+        where C: AsRef<str> {
         let len = columns.len();
         if len == 0 {
             return self;
         }
         let mut index = 0;
-        self.sql = self.sql.trim_end_matches(" WHERE ")
-            .trim_end_matches(" AND ")
-            .trim_end_matches(" OR ").to_string();
-        self.sql.push_str(" ORDER BY ");
+        self.trim_end_opt();
+        self.push_text(" ORDER BY ");
         for x in columns {
+            self.push_column(x.as_ref());
             if is_asc {
-                self.sql.push_str(format!("{} ASC", x).as_str());
+                self.push_text(" ASC");
             } else {
-                self.sql.push_str(format!("{} DESC", x, ).as_str());
+                self.push_text(" DESC");
             }
             if (index + 1) != len {
-                self.sql.push_str(" , ");
+                self.push_text(" , ");
                 index += 1;
             }
         }
         self
     }
 
-    pub fn group_by(&mut self, columns: &[&str]) -> &mut Self {
+    /// sql:   ORDER BY sql,the sql is not checked(expression,for example "count(1) DESC"),never put the input of user in it
+    pub fn order_by_raw(&mut self, sql: &str) -> &mut Self {
+        self.trim_end_opt();
+        self.push_text(format!(" ORDER BY {}", sql.trim()).as_str());
+        self
+    }
+
+    pub fn group_by<C>(&mut self, columns: &[C]) -> &mut Self
+        where C: AsRef<str> {
         let len = columns.len();
         if len == 0 {
             return self;
         }
         let mut index = 0;
-        self.sql = self.sql.trim_end_matches(" WHERE ")
-            .trim_end_matches(" AND ")
-            .trim_end_matches(" OR ").to_string();
-        self.sql.push_str(" GROUP BY ");
+        self.trim_end_opt();
+        self.push_text(" GROUP BY ");
         for x in columns {
-            self.sql.push_str(x);
+            self.push_column(x.as_ref());
             if (index + 1) != len {
-                self.sql.push_str(" , ");
+                self.push_text(" , ");
                 index += 1;
             }
         }
         self
     }
 
-    ///  sql:   column > obj
-    pub fn gt<T>(&mut self, column: &str, obj: T) -> &mut Self
-        where T: Serialize {
-        self.and();
-        let v = serde_json::to_value(obj).unwrap_or(serde_json::Value::Null);
-        self.sql.push_str(column);
-        self.sql.push_str(format!(" > {}", self.driver_type.stmt_convert(self.args.len())).as_str());
-        self.args.push(v);
+    /// sql:   GROUP BY sql,the sql is not checked(expression,for example "date(create_time)"),never put the input of user in it
+    pub fn group_by_raw(&mut self, sql: &str) -> &mut Self {
+        self.trim_end_opt();
+        self.push_text(format!(" GROUP BY {}", sql.trim()).as_str());
         self
     }
+
+    ///  sql:   column > obj
+    pub fn gt<C, T>(&mut self, column: C, obj: T) -> &mut Self
+        where C: AsRef<str>, T: Serialize {
+        self.push_compare(column.as_ref(), " > ", obj)
+    }
     ///  sql:   column >= obj
-    pub fn ge<T>(&mut self, column: &str, obj: T) -> &mut Self
-        where T: Serialize {
-        self.and();
-        let v = serde_json::to_value(obj).unwrap_or(serde_json::Value::Null);
-        self.sql.push_str(column);
-        self.sql.push_str(format!(" >= {}", self.driver_type.stmt_convert(self.args.len())).as_str());
-        self.args.push(v);
-        self
+    pub fn ge<C, T>(&mut self, column: C, obj: T) -> &mut Self
+        where C: AsRef<str>, T: Serialize {
+        self.push_compare(column.as_ref(), " >= ", obj)
     }
 
     ///  sql:   column < obj
-    pub fn lt<T>(&mut self, column: &str, obj: T) -> &mut Self
-        where T: Serialize {
-        self.and();
-        let v = serde_json::to_value(obj).unwrap_or(serde_json::Value::Null);
-        self.sql.push_str(column);
-        self.sql.push_str(format!(" < {}", self.driver_type.stmt_convert(self.args.len())).as_str());
-        self.args.push(v);
-
-        self
+    pub fn lt<C, T>(&mut self, column: C, obj: T) -> &mut Self
+        where C: AsRef<str>, T: Serialize {
+        self.push_compare(column.as_ref(), " < ", obj)
     }
 
     ///  sql:   column <= obj
-    pub fn le<T>(&mut self, column: &str, obj: T) -> &mut Self
-        where T: Serialize {
-        self.and();
-        let v = serde_json::to_value(obj).unwrap_or(serde_json::Value::Null);
-        self.sql.push_str(column);
-        self.sql.push_str(format!(" <= {}", self.driver_type.stmt_convert(self.args.len())).as_str());
-        self.args.push(v);
-        self
+    pub fn le<C, T>(&mut self, column: C, obj: T) -> &mut Self
+        where C: AsRef<str>, T: Serialize {
+        self.push_compare(column.as_ref(), " <= ", obj)
     }
 
-    pub fn between<T>(&mut self, column: &str, min: T, max: T) -> &mut Self
-        where T: Serialize {
-        self.and();
-        let min_v = serde_json::to_value(min).unwrap_or(serde_json::Value::Null);
-        let max_v = serde_json::to_value(max).unwrap_or(serde_json::Value::Null);
-        self.sql.push_str(column);
-        self.sql.push_str(format!(" BETWEEN {} AND {}", self.driver_type.stmt_convert(self.args.len()), self.driver_type.stmt_convert(self.args.len() + 1)).as_str());
-        self.args.push(min_v);
-        self.args.push(max_v);
+    pub fn between<C, T>(&mut self, column: C, min: T, max: T) -> &mut Self
+        where C: AsRef<str>, T: Serialize {
+        self.push_compare(column.as_ref(), " BETWEEN ", min);
+        self.push_text(" AND ");
+        self.push_value(max);
         self
     }
 
-    pub fn not_between<T>(&mut self, column: &str, min: T, max: T) -> &mut Self
-        where T: Serialize {
-        self.and();
-        let min_v = serde_json::to_value(min).unwrap_or(serde_json::Value::Null);
-        let max_v = serde_json::to_value(max).unwrap_or(serde_json::Value::Null);
-        self.sql.push_str(column);
-        self.sql.push_str(format!(" NOT BETWEEN {} AND {}", self.driver_type.stmt_convert(self.args.len()), self.driver_type.stmt_convert(self.args.len() + 1)).as_str());
-        self.args.push(min_v);
-        self.args.push(max_v);
+    pub fn not_between<C, T>(&mut self, column: C, min: T, max: T) -> &mut Self
+        where C: AsRef<str>, T: Serialize {
+        self.push_compare(column.as_ref(), " NOT BETWEEN ", min);
+        self.push_text(" AND ");
+        self.push_value(max);
         self
     }
 
-    pub fn like<T>(&mut self, column: &str, obj: T) -> &mut Self
-        where T: Serialize {
-        self.and();
-        let v = serde_json::to_value(obj).unwrap_or(serde_json::Value::Null);
-        self.sql.push_str(column);
-        self.sql.push_str(format!(" LIKE '%{}%'", self.driver_type.stmt_convert(self.args.len())).as_str());
-        self.args.push(v);
-        self
+    ///  sql:   column LIKE ?(the arg is "%obj%",like_left is "%obj",like_right is "obj%")
+    pub fn like<C, T>(&mut self, column: C, obj: T) -> &mut Self
+        where C: AsRef<str>, T: Serialize {
+        self.push_compare(column.as_ref(), " LIKE ", like_value(obj, "%", "%"))
     }
-    pub fn like_left<T>(&mut self, column: &str, obj: T) -> &mut Self
-        where T: Serialize {
+    pub fn like_left<C, T>(&mut self, column: C, obj: T) -> &mut Self
+        where C: AsRef<str>, T: Serialize {
+        self.push_compare(column.as_ref(), " LIKE ", like_value(obj, "%", ""))
+    }
+
+    pub fn like_right<C, T>(&mut self, column: C, obj: T) -> &mut Self
+        where C: AsRef<str>, T: Serialize {
+        self.push_compare(column.as_ref(), " LIKE ", like_value(obj, "", "%"))
+    }
+
+    pub fn not_like<C, T>(&mut self, column: C, obj: T) -> &mut Self
+        where C: AsRef<str>, T: Serialize {
+        self.push_compare(column.as_ref(), " NOT LIKE ", like_value(obj, "%", "%"))
+    }
+
+    /// case-insensitive like,postgres: column ILIKE '%obj%' ,mysql/sqlite: LOWER(column) LIKE LOWER('%obj%')
+    pub fn ilike<C, T>(&mut self, column: C, obj: T) -> &mut Self
+        where C: AsRef<str>, T: Serialize {
+        let column = self.make_column(column.as_ref());
+        self.push_operator(column, SqlOperator::ILike, like_value(obj, "%", "%"))
+    }
+
+    pub fn not_ilike<C, T>(&mut self, column: C, obj: T) -> &mut Self
+        where C: AsRef<str>, T: Serialize {
+        let column = self.make_column(column.as_ref());
+        self.push_operator(column, SqlOperator::NotILike, like_value(obj, "%", "%"))
+    }
+
+    /// regex match,postgres: column ~ pattern ,mysql: column REGEXP pattern ,sqlite not support(check() return error)
+    pub fn regex<C>(&mut self, column: C, pattern: &str) -> &mut Self
+        where C: AsRef<str> {
+        let column = self.make_column(column.as_ref());
+        self.push_operator(column, SqlOperator::Regex, pattern)
+    }
+
+    pub fn not_regex<C>(&mut self, column: C, pattern: &str) -> &mut Self
+        where C: AsRef<str> {
+        let column = self.make_column(column.as_ref());
+        self.push_operator(column, SqlOperator::NotRegex, pattern)
+    }
+
+    /// null safe equal,NULL equal NULL
+    /// postgres: column IS NOT DISTINCT FROM obj ,mysql: column <=> obj ,sqlite: column IS obj
+    pub fn is_not_distinct_from<C, T>(&mut self, column: C, obj: T) -> &mut Self
+        where C: AsRef<str>, T: Serialize {
+        let column = self.make_column(column.as_ref());
+        self.push_operator(column, SqlOperator::IsNotDistinctFrom, obj)
+    }
+
+    /// null safe not equal
+    /// postgres: column IS DISTINCT FROM obj ,mysql: NOT (column <=> obj) ,sqlite: column IS NOT obj
+    pub fn is_distinct_from<C, T>(&mut self, column: C, obj: T) -> &mut Self
+        where C: AsRef<str>, T: Serialize {
+        let column = self.make_column(column.as_ref());
+        self.push_operator(column, SqlOperator::IsDistinctFrom, obj)
+    }
+
+    /// the json field(as text) of column equal obj,the path split by "." ,array index is number
+    /// for Example:
+    /// let w = Wrapper::new(&DriverType::Postgres).json_eq("data", "user.name", "a").check().unwrap();
+    /// postgres: data#>>'{user,name}' = $1
+    /// mysql:    JSON_UNQUOTE(JSON_EXTRACT(data, '$.user.name')) = ?
+    /// sqlite:   json_extract(data, '$.user.name') = ?
+    pub fn json_eq<C, T>(&mut self, column: C, path: &str, obj: T) -> &mut Self
+        where C: AsRef<str>, T: Serialize {
+        self.push_json_compare(column.as_ref(), path, " = ", obj)
+    }
+
+    pub fn json_ne<C, T>(&mut self, column: C, path: &str, obj: T) -> &mut Self
+        where C: AsRef<str>, T: Serialize {
+        self.push_json_compare(column.as_ref(), path, " <> ", obj)
+    }
+
+    /// the json field of column is null(json null or not exist)
+    pub fn json_is_null<C>(&mut self, column: C, path: &str) -> &mut Self
+        where C: AsRef<str> {
         self.and();
-        let v = serde_json::to_value(obj).unwrap_or(serde_json::Value::Null);
-        self.sql.push_str(column);
-        self.sql.push_str(format!(" LIKE '%{}'", self.driver_type.stmt_convert(self.args.len())).as_str());
-        self.args.push(v);
+        if let SqlNode::JsonPath { column, path } = self.make_json_path(column.as_ref(), path) {
+            self.nodes.push(SqlNode::JsonIsNull { column, path });
+        }
         self
     }
 
-    pub fn like_right<T>(&mut self, column: &str, obj: T) -> &mut Self
+    fn push_json_compare<T>(&mut self, column: &str, path: &str, op: &str, obj: T) -> &mut Self
         where T: Serialize {
         self.and();
+        let node = self.make_json_path(column, path);
+        self.nodes.push(node);
+        self.push_text(op);
         let v = serde_json::to_value(obj).unwrap_or(serde_json::Value::Null);
-        self.sql.push_str(column);
-        self.sql.push_str(format!(" LIKE '{}%'", self.driver_type.stmt_convert(self.args.len())).as_str());
-        self.args.push(v);
+        self.nodes.push(SqlNode::JsonArg(v));
         self
     }
 
-    pub fn not_like<T>(&mut self, column: &str, obj: T) -> &mut Self
-        where T: Serialize {
+    pub fn is_null<C>(&mut self, column: C) -> &mut Self
+        where C: AsRef<str> {
         self.and();
-        let v = serde_json::to_value(obj).unwrap_or(serde_json::Value::Null);
-        self.sql.push_str(column);
-        self.sql.push_str(format!(" NOT LIKE '%{}%'", self.driver_type.stmt_convert(self.args.len())).as_str());
-        self.args.push(v);
+        self.push_column(column.as_ref());
+        self.push_text(" IS NULL");
         self
     }
 
-    pub fn is_null(&mut self, column: &str) -> &mut Self {
+    pub fn is_not_null<C>(&mut self, column: C) -> &mut Self
+        where C: AsRef<str> {
         self.and();
-        self.sql.push_str(column);
-        self.sql.push_str(" IS NULL");
+        self.push_column(column.as_ref());
+        self.push_text(" IS NOT NULL");
         self
     }
 
-    pub fn is_not_null(&mut self, column: &str) -> &mut Self {
+    /// sql: column IN ( ? , ? ),if obj is empty the sql is "1 = 0"(match nothing),see in_array_opt() to ignore it
+    pub fn in_array<C, T>(&mut self, column: C, obj: &[T]) -> &mut Self
+        where C: AsRef<str>, T: Serialize {
         self.and();
-        self.sql.push_str(column);
-        self.sql.push_str(" IS NOT NULL");
-        self
+        if obj.len() == 0 {
+            self.push_text("1 = 0");
+            return self;
+        }
+        self.push_in(column.as_ref(), " IN (", obj)
     }
 
-    pub fn in_array<T>(&mut self, column: &str, obj: &[T]) -> &mut Self
-        where T: Serialize {
+    /// sql: column NOT IN ( ? , ? ),if obj is empty the sql is "1 = 1"(match all),see not_in_opt() to ignore it
+    pub fn not_in<C, T>(&mut self, column: C, obj: &[T]) -> &mut Self
+        where C: AsRef<str>, T: Serialize {
         self.and();
         if obj.len() == 0 {
+            self.push_text("1 = 1");
             return self;
         }
-        let v = serde_json::to_value(obj).unwrap_or(serde_json::Value::Null);
-        self.sql.push_str(column);
-        let vec = v.as_array().unwrap();
-        let mut sqls = String::new();
-        for x in vec {
-            sqls.push_str(format!(" {} ", self.driver_type.stmt_convert(self.args.len())).as_str());
-            sqls.push_str(",");
-            self.args.push(x.clone());
+        self.push_in(column.as_ref(), " NOT IN (", obj)
+    }
+
+    fn push_in<T>(&mut self, column: &str, op: &str, obj: &[T]) -> &mut Self
+        where T: Serialize {
+        self.push_column(column);
+        self.push_text(op);
+        let mut index = 0;
+        for x in obj {
+            if index != 0 {
+                self.push_text(",");
+            }
+            self.push_text(" ");
+            self.push_value(x);
+            self.push_text(" ");
+            index += 1;
         }
-        sqls.pop();
-        self.sql.push_str(format!(" IN ({})", sqls).as_str());
+        self.push_text(")");
         self
     }
 
-    pub fn not_in<T>(&mut self, column: &str, obj: &[T]) -> &mut Self
+    /// the *_opt methods: do nothing if the obj is None(or empty slice)
+    /// for Example:
+    /// let name: Option<String> = None;
+    /// let status = Some(1);
+    /// let w = Wrapper::new(&DriverType::Mysql).eq_opt("name", name.as_ref()).eq_opt("status", status).check().unwrap();
+    /// println!("sql:{:?}", w.sql.as_str());  // sql:"status = ?"
+    pub fn eq_opt<C, T>(&mut self, column: C, obj: Option<T>) -> &mut Self
+        where C: AsRef<str>, T: Serialize {
+        match obj {
+            Some(obj) => self.eq(column, obj),
+            None => self,
+        }
+    }
+
+    pub fn ne_opt<C, T>(&mut self, column: C, obj: Option<T>) -> &mut Self
+        where C: AsRef<str>, T: Serialize {
+        match obj {
+            Some(obj) => self.ne(column, obj),
+            None => self,
+        }
+    }
+
+    pub fn gt_opt<C, T>(&mut self, column: C, obj: Option<T>) -> &mut Self
+        where C: AsRef<str>, T: Serialize {
+        match obj {
+            Some(obj) => self.gt(column, obj),
+            None => self,
+        }
+    }
+
+    pub fn ge_opt<C, T>(&mut self, column: C, obj: Option<T>) -> &mut Self
+        where C: AsRef<str>, T: Serialize {
+        match obj {
+            Some(obj) => self.ge(column, obj),
+            None => self,
+        }
+    }
+
+    pub fn lt_opt<C, T>(&mut self, column: C, obj: Option<T>) -> &mut Self
+        where C: AsRef<str>, T: Serialize {
+        match obj {
+            Some(obj) => self.lt(column, obj),
+            None => self,
+        }
+    }
+
+    pub fn le_opt<C, T>(&mut self, column: C, obj: Option<T>) -> &mut Self
+        where C: AsRef<str>, T: Serialize {
+        match obj {
+            Some(obj) => self.le(column, obj),
+            None => self,
+        }
+    }
+
+    /// both: column BETWEEN min AND max ,only min: column >= min ,only max: column <= max
+    pub fn between_opt<C, T>(&mut self, column: C, min: Option<T>, max: Option<T>) -> &mut Self
+        where C: AsRef<str>, T: Serialize {
+        match (min, max) {
+            (Some(min), Some(max)) => self.between(column, min, max),
+            (Some(min), None) => self.ge(column, min),
+            (None, Some(max)) => self.le(column, max),
+            (None, None) => self,
+        }
+    }
+
+    pub fn like_opt<C, T>(&mut self, column: C, obj: Option<T>) -> &mut Self
+        where C: AsRef<str>, T: Serialize {
+        match obj {
+            Some(obj) => self.like(column, obj),
+            None => self,
+        }
+    }
+
+    pub fn like_left_opt<C, T>(&mut self, column: C, obj: Option<T>) -> &mut Self
+        where C: AsRef<str>, T: Serialize {
+        match obj {
+            Some(obj) => self.like_left(column, obj),
+            None => self,
+        }
+    }
+
+    pub fn like_right_opt<C, T>(&mut self, column: C, obj: Option<T>) -> &mut Self
+        where C: AsRef<str>, T: Serialize {
+        match obj {
+            Some(obj) => self.like_right(column, obj),
+            None => self,
+        }
+    }
+
+    pub fn not_like_opt<C, T>(&mut self, column: C, obj: Option<T>) -> &mut Self
+        where C: AsRef<str>, T: Serialize {
+        match obj {
+            Some(obj) => self.not_like(column, obj),
+            None => self,
+        }
+    }
+
+    pub fn ilike_opt<C, T>(&mut self, column: C, obj: Option<T>) -> &mut Self
+        where C: AsRef<str>, T: Serialize {
+        match obj {
+            Some(obj) => self.ilike(column, obj),
+            None => self,
+        }
+    }
+
+    pub fn in_array_opt<C, T>(&mut self, column: C, obj: Option<&[T]>) -> &mut Self
+        where C: AsRef<str>, T: Serialize {
+        match obj {
+            Some(obj) if !obj.is_empty() => self.in_array(column, obj),
+            _ => self,
+        }
+    }
+
+    pub fn not_in_opt<C, T>(&mut self, column: C, obj: Option<&[T]>) -> &mut Self
+        where C: AsRef<str>, T: Serialize {
+        match obj {
+            Some(obj) if !obj.is_empty() => self.not_in(column, obj),
+            _ => self,
+        }
+    }
+
+    /// all_eq but ignore the null value fields,the conditions link by AND
+    /// for Example:
+    /// let w = Wrapper::new(&DriverType::Mysql).all_eq_not_null(json!({"name": null, "status": 1, "type": 2})).check().unwrap();
+    /// println!("sql:{:?}", w.sql.as_str());  // sql:"status = ? AND type = ?"
+    pub fn all_eq_not_null<T>(&mut self, arg: T) -> &mut Self
         where T: Serialize {
+        let v = serde_json::to_value(arg).unwrap_or(serde_json::Value::Null);
+        let map = match v.as_object() {
+            Some(map) => map,
+            None => {
+                self.error = Some(Error::from("[rbatis] wrapper all_eq only support object/map struct!"));
+                return self;
+            }
+        };
+        for (k, v) in map {
+            if !v.is_null() {
+                self.eq(k.as_str(), v);
+            }
+        }
+        self
+    }
+
+    /// sql:   column IN (sub query)
+    /// the sub query can be an raw sql or Wrapper,the args of Wrapper will push into self
+    /// for Example:
+    /// let sub = Wrapper::new(&DriverType::Postgres).push_sql("SELECT user_id FROM biz_order WHERE ").eq("status", 1).check().unwrap();
+    /// let w = Wrapper::new(&DriverType::Postgres).eq("name", "a").in_sql("id", &sub).check().unwrap();
+    /// println!("sql:{:?}", w.sql.as_str());  // sql:"name = $1 AND id IN (SELECT user_id FROM biz_order WHERE status = $2)"
+    pub fn in_sql<C, S>(&mut self, column: C, sub: &S) -> &mut Self
+        where C: AsRef<str>, S: SubQuery + ?Sized {
+        self.push_sub_query(column.as_ref(), " IN ", sub)
+    }
+
+    /// sql:   column NOT IN (sub query)
+    pub fn not_in_sql<C, S>(&mut self, column: C, sub: &S) -> &mut Self
+        where C: AsRef<str>, S: SubQuery + ?Sized {
+        self.push_sub_query(column.as_ref(), " NOT IN ", sub)
+    }
+
+    /// sql:   EXISTS (sub query)
+    /// for Example:
+    /// let w = Wrapper::new(&DriverType::Mysql).exists("SELECT 1 FROM biz_order WHERE biz_order.user_id = biz_user.id").check().unwrap();
+    pub fn exists<S>(&mut self, sub: &S) -> &mut Self
+        where S: SubQuery + ?Sized {
+        self.push_sub_query("", "EXISTS ", sub)
+    }
+
+    /// sql:   NOT EXISTS (sub query)
+    pub fn not_exists<S>(&mut self, sub: &S) -> &mut Self
+        where S: SubQuery + ?Sized {
+        self.push_sub_query("", "NOT EXISTS ", sub)
+    }
+
+    fn push_sub_query<S>(&mut self, column: &str, op: &str, sub: &S) -> &mut Self
+        where S: SubQuery + ?Sized {
+        let nodes = match sub.sub_query() {
+            Ok(v) => v,
+            Err(e) => {
+                self.error = Some(e);
+                return self;
+            }
+        };
         self.and();
-        let v = serde_json::to_value(obj).unwrap_or(serde_json::Value::Null);
-        self.sql.push_str(column);
-        let vec = v.as_array().unwrap();
-        let mut sqls = String::new();
-        for x in vec {
-            sqls.push_str(format!(" {} ", self.driver_type.stmt_convert(self.args.len())).as_str());
-            sqls.push_str(",");
-            self.args.push(x.clone());
+        if !column.is_empty() {
+            self.push_column(column);
         }
-        sqls.pop();
-        self.sql.push_str(format!(" NOT IN ({})", sqls).as_str());
+        self.push_text(op);
+        self.push_text("(");
+        self.nodes.push(SqlNode::Group(nodes));
+        self.push_text(")");
         self
     }
 
     pub fn trim_and(&mut self) -> &mut Self {
-        self.sql = self.sql
-            .trim_start_matches(" AND ")
-            .trim_end_matches(" AND ")
-            .trim_start_matches("AND ")
-            .trim_end_matches(" AND")
-            .to_string();
+        self.trim_nodes(|sql| sql.trim_start_matches(" AND ").trim_start_matches("AND "),
+                        |sql| sql.trim_end_matches(" AND ").trim_end_matches(" AND"));
         self
     }
 
     pub fn trim_or(&mut self) -> &mut Self {
-        self.sql = self.sql.
-            trim_start_matches(" OR ")
-            .trim_end_matches(" OR ")
-            .trim_start_matches("OR ")
-            .trim_end_matches(" OR")
-            .to_string();
+        self.trim_nodes(|sql| sql.trim_start_matches(" OR ").trim_start_matches("OR "),
+                        |sql| sql.trim_end_matches(" OR ").trim_end_matches(" OR"));
+        self
+    }
+
+    /// remove the ORDER BY(it must be the last part of the sql),for example count(1) not need it
+    pub fn trim_order_by(&mut self) -> &mut Self {
+        if self.nodes.is_empty() {
+            self.nodes = self.to_nodes();
+        }
+        let index = self.nodes.iter().rposition(|x| match x {
+            SqlNode::Sql(sql) => sql.contains("ORDER BY"),
+            _ => false
+        });
+        if let Some(index) = index {
+            self.nodes.truncate(index + 1);
+            if let Some(SqlNode::Sql(last)) = self.nodes.last_mut() {
+                let end = last.rfind("ORDER BY").unwrap_or(last.len());
+                *last = last[..end].trim_end().to_string();
+            }
+        }
         self
     }
+
+    /// trim the first and the last sql node
+    fn trim_nodes<S, E>(&mut self, start: S, end: E)
+        where S: Fn(&str) -> &str, E: Fn(&str) -> &str {
+        if self.nodes.is_empty() {
+            let sql = end(start(&self.sql)).to_string();
+            self.sql = sql;
+            return;
+        }
+        if let Some(SqlNode::Sql(first)) = self.nodes.first_mut() {
+            *first = start(first).to_string();
+        }
+        if let Some(SqlNode::Sql(last)) = self.nodes.last_mut() {
+            *last = end(last).to_string();
+        }
+    }
+}
+
+impl std::fmt::Debug for Wrapper {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut sql = String::new();
+        if let Err(e) = render_nodes(&self.driver_type, &self.to_nodes(), false, true, &mut sql, &mut vec![]) {
+            sql = format!("{:?}", e);
+        }
+        f.debug_struct("Wrapper")
+            .field("driver_type", &self.driver_type)
+            .field("sql", &sql)
+            .field("error", &self.error)
+            .finish()
+    }
+}
+
+/// render nodes to sql,args. the "$n" of raw sql in group is offset by the args before the group
+/// inline: render the args to sql(only for log)
+fn render_nodes(driver_type: &DriverType, nodes: &[SqlNode], is_group: bool, inline: bool, sql: &mut String, args: &mut Vec<Value>) -> Result<(), Error> {
+    let offset = args.len();
+    for node in nodes {
+        match node {
+            SqlNode::Sql(s) => {
+                if is_group && !inline {
+                    sql.push_str(offset_stmt(driver_type, s, offset).as_str());
+                } else {
+                    sql.push_str(s);
+                }
+            }
+            SqlNode::Arg(v) => {
+                if inline {
+                    sql.push_str(inline_value(v).as_str());
+                } else {
+                    sql.push_str(driver_type.stmt_convert(args.len()).as_str());
+                    args.push(v.clone());
+                }
+            }
+            SqlNode::JsonArg(v) => {
+                let v = json_arg_value(driver_type, v);
+                if inline {
+                    sql.push_str(inline_value(&v).as_str());
+                } else {
+                    sql.push_str(driver_type.stmt_convert(args.len()).as_str());
+                    args.push(v);
+                }
+            }
+            SqlNode::RawArg(v) => {
+                if !inline {
+                    args.push(v.clone());
+                }
+            }
+            SqlNode::Column { name, quote } => {
+                sql.push_str(quote_column(driver_type, name, *quote).as_str());
+            }
+            SqlNode::Group(nodes) => {
+                render_nodes(driver_type, nodes, true, inline, sql, args)?;
+            }
+            SqlNode::Operator { op, column, arg } => {
+                let mut column_sql = String::new();
+                render_nodes(driver_type, std::slice::from_ref(column.as_ref()), false, inline, &mut column_sql, args)?;
+                let mut arg_sql = String::new();
+                render_nodes(driver_type, std::slice::from_ref(arg.as_ref()), false, inline, &mut arg_sql, args)?;
+                sql.push_str(driver_type.operator_sql(op, &column_sql, &arg_sql)?.as_str());
+            }
+            SqlNode::JsonPath { column, path } => {
+                let mut column_sql = String::new();
+                render_nodes(driver_type, std::slice::from_ref(column.as_ref()), false, inline, &mut column_sql, args)?;
+                sql.push_str(driver_type.json_path_sql(&column_sql, path)?.as_str());
+            }
+            SqlNode::JsonIsNull { column, path } => {
+                let mut column_sql = String::new();
+                render_nodes(driver_type, std::slice::from_ref(column.as_ref()), false, inline, &mut column_sql, args)?;
+                sql.push_str(driver_type.json_is_null_sql(&column_sql, path)?.as_str());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// postgres: 1 => '1',true => 'true'. other driver keep the value
+fn json_arg_value(driver_type: &DriverType, v: &Value) -> Value {
+    match (driver_type, v) {
+        (DriverType::Postgres, Value::Null) | (DriverType::Postgres, Value::String(_)) => v.clone(),
+        (DriverType::Postgres, v) => Value::String(v.to_string()),
+        _ => v.clone(),
+    }
+}
+
+/// like value: obj => %obj% ,%obj ,obj%
+fn like_value<T>(obj: T, prefix: &str, suffix: &str) -> Value where T: Serialize {
+    match serde_json::to_value(obj).unwrap_or(serde_json::Value::Null) {
+        Value::Null => Value::Null,
+        Value::String(s) => Value::String(format!("{}{}{}", prefix, s, suffix)),
+        v => Value::String(format!("{}{}{}", prefix, v, suffix)),
+    }
+}
+
+/// for example: 'a''b' ,NULL ,1
+fn inline_value(v: &Value) -> String {
+    match v {
+        Value::Null => "NULL".to_string(),
+        Value::String(s) => format!("'{}'", s.replace("'", "''")),
+        Value::Bool(_) | Value::Number(_) => v.to_string(),
+        _ => format!("'{}'", v.to_string().replace("'", "''")),
+    }
+}
+
+/// mysql: `name` ,postgres/sqlite: "name"
+fn quote_column(driver_type: &DriverType, column: &str, quote: bool) -> String {
+    if !quote {
+        return column.to_string();
+    }
+    let quote = match driver_type {
+        DriverType::Mysql => "`",
+        _ => "\"",
+    };
+    let parts: Vec<String> = column.split(".").map(|part| {
+        if unquote(part).len() != part.len() {
+            part.to_string()
+        } else {
+            format!("{}{}{}", quote, part, quote)
+        }
+    }).collect();
+    parts.join(".")
+}
+
+/// postgres: offset the "$n" of sql, for example "a = $1" offset 2 => "a = $3"
+fn offset_stmt(driver_type: &DriverType, sql: &str, offset: usize) -> String {
+    if !driver_type.eq(&DriverType::Postgres) || offset == 0 {
+        return sql.to_string();
+    }
+    let mut new_sql = String::with_capacity(sql.len());
+    let mut chars = sql.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '$' {
+            new_sql.push(c);
+            continue;
+        }
+        let mut num = String::new();
+        while let Some(n) = chars.peek() {
+            if !n.is_ascii_digit() {
+                break;
+            }
+            num.push(*n);
+            chars.next();
+        }
+        match num.parse::<usize>() {
+            Ok(index) if index > 0 => new_sql.push_str(driver_type.stmt_convert(index - 1 + offset).as_str()),
+            _ => {
+                new_sql.push(c);
+                new_sql.push_str(num.as_str());
+            }
+        }
+    }
+    new_sql
+}
+
+/// no column,arg,and the sql is blank
+fn is_empty_nodes(nodes: &[SqlNode]) -> bool {
+    nodes.iter().all(|x| match x {
+        SqlNode::Sql(s) => s.trim().is_empty(),
+        SqlNode::Group(nodes) => is_empty_nodes(nodes),
+        _ => false,
+    })
+}
+
+/// the column of table,#[derive(CRUDEnable)] generate it for every field in <Name>Columns,the Wrapper methods accept Column or &str
+/// for Example:
+/// let w = Wrapper::new(&DriverType::Mysql).eq(BizActivityColumns::status, 1).order_by(true, &[BizActivityColumns::create_time]).check().unwrap();
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct Column {
+    pub name: &'static str,
+}
+
+impl Column {
+    pub const fn new(name: &'static str) -> Self {
+        Self { name }
+    }
+}
+
+impl AsRef<str> for Column {
+    fn as_ref(&self) -> &str {
+        self.name
+    }
+}
+
+impl std::fmt::Display for Column {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name)
+    }
+}
+
+/// identifier: letters,digits,_ ,or quoted by `` or ""
+fn is_identifier(part: &str) -> bool {
+    let name = unquote(part);
+    if name.is_empty() {
+        return false;
+    }
+    name.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// `name` or "name" => name
+fn unquote(part: &str) -> &str {
+    if part.len() >= 2 && ((part.starts_with('`') && part.ends_with('`')) || (part.starts_with('"') && part.ends_with('"'))) {
+        return &part[1..part.len() - 1];
+    }
+    part
+}
+
+/// the sub query of Wrapper::in_sql(),Wrapper::exists()...
+/// it can be an raw sql(&str,String) or an Wrapper(with args)
+pub trait SubQuery {
+    fn sub_query(&self) -> Result<Vec<SqlNode>, Error>;
+}
+
+impl SubQuery for str {
+    fn sub_query(&self) -> Result<Vec<SqlNode>, Error> {
+        Ok(vec![SqlNode::Sql(self.trim().to_string())])
+    }
+}
+
+impl SubQuery for String {
+    fn sub_query(&self) -> Result<Vec<SqlNode>, Error> {
+        self.as_str().sub_query()
+    }
+}
+
+impl SubQuery for Wrapper {
+    fn sub_query(&self) -> Result<Vec<SqlNode>, Error> {
+        if let Some(e) = &self.error {
+            return Err(e.clone());
+        }
+        let mut w = self.clone();
+        w.nodes = w.to_nodes();
+        w.trim_and();
+        w.trim_or();
+        Ok(w.nodes)
+    }
 }
 
 pub struct Case {
@@ -523,7 +1415,7 @@ mod test {
     use rbatis_core::db::DriverType;
 
     use crate::utils::bencher::Bencher;
-    use crate::wrapper::{Case, Wrapper};
+    use crate::wrapper::{Case, Column, Wrapper};
 
     #[test]
     fn test_select() {
@@ -587,6 +1479,273 @@ mod test {
         assert_eq!(ms.len(), w.args.len());
     }
 
+    #[test]
+    fn test_nested() {
+        let w = Wrapper::new(&DriverType::Postgres)
+            .eq("a", 1)
+            .and_nested(|w| w.eq("b", 2).or().eq("c", 3))
+            .or_nested(|w| w.in_array("d", &[4, 5]).ne("e", 6).and_nested(|w| w.eq("f", 7).or().eq("g", 8)))
+            .check().unwrap();
+        println!("sql:{:?}", w.sql.as_str());
+        assert_eq!(w.sql, "a = $1 AND (b = $2 OR c = $3) OR (d IN ( $4 , $5 ) AND e <> $6 AND (f = $7 OR g = $8))");
+        assert_eq!(w.args, vec![json!(1), json!(2), json!(3), json!(4), json!(5), json!(6), json!(7), json!(8)]);
+
+        let w = Wrapper::new(&DriverType::Mysql)
+            .or_nested(|w| w.eq("b", 2).eq("c", 3))
+            .and_nested(|w| w)
+            .eq("a", 1)
+            .check().unwrap();
+        assert_eq!(w.sql, "(b = ? AND c = ?) AND a = ?");
+        assert_eq!(w.args, vec![json!(2), json!(3), json!(1)]);
+
+        let w = Wrapper::new(&DriverType::Postgres).eq("a", "1").check().unwrap();
+        let w2 = Wrapper::new(&DriverType::Postgres).eq("b", "2")
+            .and()
+            .push_wrapper(&w)
+            .check().unwrap();
+        assert_eq!(w2.sql, "b = $1 AND a = $2");
+    }
+
+    #[test]
+    fn test_sub_query() {
+        let sub = Wrapper::new(&DriverType::Postgres)
+            .push_sql("SELECT user_id FROM biz_order WHERE ")
+            .eq("status", 1)
+            .gt("amount", 2)
+            .check().unwrap();
+        let w = Wrapper::new(&DriverType::Postgres)
+            .eq("name", "a")
+            .in_sql("id", &sub)
+            .or()
+            .not_in_sql("id", "SELECT user_id FROM biz_black")
+            .not_exists(&sub)
+            .check().unwrap();
+        println!("sql:{:?}", w.sql.as_str());
+        assert_eq!(w.sql, "name = $1 AND id IN (SELECT user_id FROM biz_order WHERE status = $2 AND amount > $3) OR id NOT IN (SELECT user_id FROM biz_black) AND NOT EXISTS (SELECT user_id FROM biz_order WHERE status = $4 AND amount > $5)");
+        assert_eq!(w.args, vec![json!("a"), json!(1), json!(2), json!(1), json!(2)]);
+
+        let w = Wrapper::new(&DriverType::Mysql)
+            .exists(&"SELECT 1 FROM biz_order WHERE biz_order.user_id = biz_user.id".to_string())
+            .eq("id", 1)
+            .check().unwrap();
+        assert_eq!(w.sql, "EXISTS (SELECT 1 FROM biz_order WHERE biz_order.user_id = biz_user.id) AND id = ?");
+
+        let mut bad = Wrapper::new(&DriverType::Mysql);
+        bad.all_eq(1);
+        assert!(Wrapper::new(&DriverType::Mysql).in_sql("id", &bad).check().is_err());
+    }
+
+    #[test]
+    fn test_limit_join() {
+        let w = Wrapper::new(&DriverType::Mysql)
+            .select(&["biz_user.*", "o.amount"])
+            .left_join("biz_order o", "o.user_id", "biz_user.id")
+            .inner_join("biz_dept d", "d.id", "biz_user.dept_id")
+            .eq("o.status", 1)
+            .limit(10)
+            .offset(20)
+            .check().unwrap();
+        assert_eq!(w.select_columns, Some("biz_user.*,o.amount".to_string()));
+        assert_eq!(w.join_sql, " LEFT JOIN biz_order o ON o.user_id = biz_user.id INNER JOIN biz_dept d ON d.id = biz_user.dept_id");
+        assert_eq!(w.sql, "o.status = ?");
+        assert_eq!(w.limit_sql().unwrap(), " LIMIT 20,10");
+        let w = Wrapper::new(&DriverType::Postgres).quote_columns()
+            .select(&["biz_user.*", "o.amount"])
+            .left_join("biz_order o", "o.user_id", "biz_user.id")
+            .check().unwrap();
+        assert_eq!(w.select_columns, Some("\"biz_user\".*,\"o\".\"amount\"".to_string()));
+        assert_eq!(w.join_sql, " LEFT JOIN \"biz_order\" \"o\" ON \"o\".\"user_id\" = \"biz_user\".\"id\"");
+        assert!(Wrapper::new(&DriverType::Mysql).select(&["id", "(select pwd from user)"]).check().is_err());
+        assert!(Wrapper::new(&DriverType::Mysql).select(&["id;drop table a"]).check().is_err());
+        assert!(Wrapper::new(&DriverType::Mysql).strict_columns("id,name").select(&["id", "pwd"]).check().is_err());
+        assert!(Wrapper::new(&DriverType::Mysql).left_join("biz_order o ON 1=1 --", "o.id", "id").check().is_err());
+        assert!(Wrapper::new(&DriverType::Mysql).inner_join("biz_order", "o.id OR 1=1", "id").check().is_err());
+
+        let w = Wrapper::new(&DriverType::Postgres).limit(10).check().unwrap();
+        assert_eq!(w.limit_sql().unwrap(), " LIMIT 10 OFFSET 0");
+        let w = Wrapper::new(&DriverType::Sqlite).offset(5).check().unwrap();
+        assert_eq!(w.limit_sql().unwrap(), format!(" LIMIT {} OFFSET 5", i64::MAX));
+        assert_eq!(Wrapper::new(&DriverType::Mysql).limit_sql().unwrap(), "");
+        assert!(Wrapper::new(&DriverType::Mysql).select::<&str>(&[]).check().is_err());
+
+        //old json without the new fields
+        let w: Wrapper = serde_json::from_str(r#"{"driver_type":"Mysql","sql":"id = ?","args":[1],"error":null,"checked":true}"#).unwrap();
+        assert_eq!(w.limit, None);
+    }
+
+    #[test]
+    fn test_column() {
+        let w = Wrapper::new(&DriverType::Mysql)
+            .quote_columns()
+            .eq("name", "a")
+            .eq("o.status", 1)
+            .eq("`type`", 2)
+            .order_by(false, &["create_time"])
+            .check().unwrap();
+        assert_eq!(w.sql, "`name` = ? AND `o`.`status` = ? AND `type` = ? ORDER BY `create_time` DESC");
+        let w = Wrapper::new(&DriverType::Postgres)
+            .quote_columns()
+            .in_array("id", &[1])
+            .group_by(&["name"])
+            .check().unwrap();
+        assert_eq!(w.sql, "\"id\" IN ( $1 ) GROUP BY \"name\"");
+
+        assert!(Wrapper::new(&DriverType::Mysql).order_by(true, &["id;DROP TABLE biz_activity"]).check().is_err());
+        assert!(Wrapper::new(&DriverType::Mysql).order_by(true, &["count(1) desc"]).check().is_err());
+        let w = Wrapper::new(&DriverType::Mysql).eq("t.a", 1).group_by(&["t.a"]).order_by(true, &["t.b"]).check().unwrap();
+        assert_eq!(w.sql, "t.a = ? GROUP BY t.a ORDER BY t.b ASC");
+        //the expressions use the raw methods
+        let w = Wrapper::new(&DriverType::Postgres)
+            .push_sql("lower(name) = ").push_stmt_arg("a")
+            .group_by_raw("date(create_time)")
+            .order_by_raw("count(1) DESC")
+            .check().unwrap();
+        assert_eq!(w.sql, "lower(name) = $1 GROUP BY date(create_time) ORDER BY count(1) DESC");
+        assert!(Wrapper::new(&DriverType::Mysql).eq("id = 1 OR 1", 1).check().is_err());
+        assert!(Wrapper::new(&DriverType::Sqlite).like("`na`me`", 1).check().is_err());
+        assert!(Wrapper::new(&DriverType::Mysql).eq("", 1).check().is_err());
+
+        let table_fields = "id,user_name AS name,status";
+        let w = Wrapper::new(&DriverType::Mysql)
+            .strict_columns(table_fields)
+            .eq("user_name", "a")
+            .order_by(true, &["status"])
+            .check().unwrap();
+        assert_eq!(w.sql, "user_name = ? ORDER BY status ASC");
+        assert!(Wrapper::new(&DriverType::Mysql).strict_columns(table_fields).order_by(true, &["password"]).check().is_err());
+        assert!(Wrapper::new(&DriverType::Mysql).strict_columns(table_fields).eq("name", "a").check().is_err());
+    }
+
+    #[test]
+    fn test_typed_column() {
+        //same as the <Name>Columns of #[derive(CRUDEnable)]
+        struct BizActivityColumns;
+        #[allow(non_upper_case_globals)]
+        impl BizActivityColumns {
+            pub const status: Column = Column::new("status");
+            pub const name: Column = Column::new("name");
+        }
+        let w = Wrapper::new(&DriverType::Mysql)
+            .eq(BizActivityColumns::status, 1)
+            .in_array(BizActivityColumns::name, &["a", "b"])
+            .order_by(true, &[BizActivityColumns::status, BizActivityColumns::name])
+            .check().unwrap();
+        assert_eq!(w.sql, "status = ? AND name IN ( ? , ? ) ORDER BY status ASC , name ASC");
+        let column = "status".to_string();
+        let w = Wrapper::new(&DriverType::Mysql).eq(&column, 1).group_by(&[column.as_str()]).check().unwrap();
+        assert_eq!(w.sql, "status = ? GROUP BY status");
+    }
+
+    #[test]
+    fn test_render() {
+        let w = Wrapper::new(&DriverType::Mysql)
+            .quote_columns()
+            .eq("a", 1)
+            .and_nested(|w| w.eq("b", "x").or().in_array("c", &[2, 3]))
+            .clone();
+        let mysql = w.render(&DriverType::Mysql).unwrap();
+        assert_eq!(mysql.sql, "`a` = ? AND (`b` = ? OR `c` IN ( ? , ? ))");
+        let pg = w.render(&DriverType::Postgres).unwrap();
+        assert_eq!(pg.sql, "\"a\" = $1 AND (\"b\" = $2 OR \"c\" IN ( $3 , $4 ))");
+        assert_eq!(pg.args, vec![json!(1), json!("x"), json!(2), json!(3)]);
+
+        //merge without renumber
+        let w1 = Wrapper::new(&DriverType::Postgres).eq("a", 1).clone();
+        let w2 = Wrapper::new(&DriverType::Mysql).eq("b", 2).and().push_wrapper(&w1).clone();
+        assert_eq!(w2.render(&DriverType::Postgres).unwrap().sql, "b = $1 AND a = $2");
+        assert_eq!(w2.render(&DriverType::Sqlite).unwrap().sql, "b = ? AND a = ?");
+
+        //old json without nodes
+        let w: Wrapper = serde_json::from_str(r#"{"driver_type":"Postgres","sql":"id = $1","args":[1],"error":null,"checked":true}"#).unwrap();
+        let w = Wrapper::new(&DriverType::Postgres).eq("a", 1).and().push_wrapper(&w).check().unwrap();
+        assert_eq!(w.sql, "a = $1 AND id = $2");
+
+        let w = Wrapper::new(&DriverType::Postgres).eq("name", "it's").ne("id", 1).is_null("b").clone();
+        assert_eq!(format!("{:?}", w), "Wrapper { driver_type: Postgres, sql: \"name = 'it''s' AND id <> 1 AND b IS NULL\", error: None }");
+    }
+
+    #[test]
+    fn test_operator() {
+        let w = Wrapper::new(&DriverType::Mysql)
+            .ilike("name", "ab")
+            .regex("code", "^A[0-9]+")
+            .is_not_distinct_from("parent_id", serde_json::Value::Null)
+            .is_distinct_from("status", 1)
+            .json_eq("data", "user.name", "a")
+            .json_is_null("data", "tags.0")
+            .clone();
+        let pg = w.render(&DriverType::Postgres).unwrap();
+        assert_eq!(pg.sql, "name ILIKE $1 AND code ~ $2 AND parent_id IS NOT DISTINCT FROM $3 AND status IS DISTINCT FROM $4 AND data#>>'{user,name}' = $5 AND data#>>'{tags,0}' IS NULL");
+        assert_eq!(pg.args, vec![json!("%ab%"), json!("^A[0-9]+"), json!(null), json!(1), json!("a")]);
+        let mysql = w.render(&DriverType::Mysql).unwrap();
+        assert_eq!(mysql.sql, "LOWER(name) LIKE LOWER(?) AND code REGEXP ? AND parent_id <=> ? AND NOT (status <=> ?) AND JSON_UNQUOTE(JSON_EXTRACT(data, '$.user.name')) = ? AND COALESCE(JSON_TYPE(JSON_EXTRACT(data, '$.tags[0]')), 'NULL') = 'NULL'");
+        //sqlite not support regex
+        assert!(w.render(&DriverType::Sqlite).is_err());
+        let sqlite = Wrapper::new(&DriverType::Sqlite).not_ilike("name", "ab").json_ne("data", "age", 1).check().unwrap();
+        assert_eq!(sqlite.sql, "LOWER(name) NOT LIKE LOWER(?) AND json_extract(data, '$.age') <> ?");
+        assert_eq!(sqlite.args, vec![json!("%ab%"), json!(1)]);
+        //postgres ->> is text,the number,bool bind as text
+        let w = Wrapper::new(&DriverType::Postgres).json_eq("data", "age", 1).json_ne("data", "user.vip", true).check().unwrap();
+        assert_eq!(w.sql, "data->>'age' = $1 AND data#>>'{user,vip}' <> $2");
+        assert_eq!(w.args, vec![json!("1"), json!("true")]);
+        let mysql = w.render(&DriverType::Mysql).unwrap();
+        assert_eq!(mysql.args, vec![json!(1), json!(true)]);
+
+        assert!(Wrapper::new(&DriverType::Postgres).json_eq("data", "a' OR '1", 1).check().is_err());
+        let w = Wrapper::new(&DriverType::Postgres).quote_columns().json_eq("data", "name", "a").clone();
+        assert_eq!(format!("{:?}", w), "Wrapper { driver_type: Postgres, sql: \"\\\"data\\\"->>'name' = 'a'\", error: None }");
+    }
+
+    #[test]
+    fn test_opt() {
+        let name: Option<String> = None;
+        let ids: Vec<i64> = vec![];
+        let w = Wrapper::new(&DriverType::Mysql)
+            .eq_opt("name", name.as_ref())
+            .eq_opt("status", Some(1))
+            .like_opt("title", name.as_ref())
+            .between_opt("age", Some(18), None)
+            .between_opt("create_time", None::<&str>, None)
+            .in_array_opt("id", Some(ids.as_slice()))
+            .not_in_opt::<_, i64>("type", None)
+            .le_opt("amount", Some(10))
+            .check().unwrap();
+        assert_eq!(w.sql, "status = ? AND age >= ? AND amount <= ?");
+        assert_eq!(w.args, vec![json!(1), json!(18), json!(10)]);
+
+        //not ignore the empty slice
+        let w = Wrapper::new(&DriverType::Mysql)
+            .in_array("id", &ids)
+            .or()
+            .not_in("id", &ids)
+            .check().unwrap();
+        assert_eq!(w.sql, "1 = 0 OR 1 = 1");
+        assert!(w.args.is_empty());
+        let w = Wrapper::new(&DriverType::Mysql).eq("status", 1).in_array("id", &ids).check().unwrap();
+        assert_eq!(w.sql, "status = ? AND 1 = 0");
+        let w = Wrapper::new(&DriverType::Mysql).eq("status", 1).in_array_opt("id", Some(ids.as_slice())).check().unwrap();
+        assert_eq!(w.sql, "status = ?");
+
+        let w = Wrapper::new(&DriverType::Postgres)
+            .all_eq_not_null(json!({"name": null, "status": 1, "type": 2}))
+            .in_array_opt("id", Some(&[1, 2][..]))
+            .check().unwrap();
+        assert_eq!(w.sql, "status = $1 AND type = $2 AND id IN ( $3 , $4 )");
+        assert!(Wrapper::new(&DriverType::Mysql).all_eq_not_null(1).check().is_err());
+    }
+
+    #[test]
+    fn test_like() {
+        let w = Wrapper::new(&DriverType::Postgres)
+            .like("a", "x")
+            .like_left("b", "x")
+            .like_right("c", "x")
+            .not_like("d", 1)
+            .check().unwrap();
+        assert_eq!(w.sql, "a LIKE $1 AND b LIKE $2 AND c LIKE $3 AND d NOT LIKE $4");
+        assert_eq!(w.args, vec![json!("%x%"), json!("%x"), json!("x%"), json!("%1%")]);
+    }
+
     #[test]
     fn test_do_if() {
         let p = Option::<i32>::Some(1);